@@ -0,0 +1,525 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::str::FromStr;
+use std::convert::TryInto;
+
+/// attachment.rs implements the legacy mail and Usenet attachment encodings uuencode, xxencode, yEnc and BinHex 4.0
+
+#[derive(Debug, Clone)]
+pub enum Variant {
+    Uuencode,
+    Xxencode,
+    Yenc,
+    BinHex,
+}
+
+const XXENCODE_ALPHABET: & [u8; 64] = b"+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+const BINHEX_ALPHABET: & [u8; 64] = b"!\"#$%&'()*+,-012345689@ABCDEFGHIJKLMNPQRSTUVXYZ[`abcdefhijklmpqr";
+
+pub const BINHEX_HEADER: &str = "(This file must be converted with BinHex 4.0)";
+
+//File name, type and creator written to the headers when encoding
+const FILE_NAME: &str = "data.bin";
+const BINHEX_TYPE: & [u8; 4] = b"????";
+const BINHEX_CREATOR: & [u8; 4] = b"????";
+
+//Number of bytes encoded on each uuencode/xxencode line
+const UUENCODE_LINE_BYTES: usize = 45;
+
+//Maximum number of characters per line
+const YENC_LINE_LENGTH: usize = 128;
+const BINHEX_LINE_LENGTH: usize = 64;
+
+//BinHex run length encoding marker
+const RLE_MARKER: u8 = 0x90;
+
+pub struct AttachmentEncoding;
+
+impl AttachmentEncoding {
+    pub fn decode(string: &str, variant: Variant) -> Result<Vec<u8>, ()> {
+        match variant {
+            Variant::Uuencode => Self::decode_uu(string, |x| if (b' '..=b'`').contains(&x) { Some((x - b' ') & 0x3F) } else { None }),
+            Variant::Xxencode => Self::decode_uu(string, |x| XXENCODE_ALPHABET.iter().position(|&y| y == x).map(|y| y as u8)),
+            Variant::Yenc => Self::decode_yenc(string),
+            Variant::BinHex => Self::decode_binhex(string),
+        }
+    }
+
+    pub fn encode(bytes: & [u8], variant: Variant) -> String {
+        match variant {
+            Variant::Uuencode => Self::encode_uu(bytes, |x| if x == 0 { '`' } else { (x + b' ') as char }),
+            Variant::Xxencode => Self::encode_uu(bytes, |x| XXENCODE_ALPHABET[x as usize] as char),
+            Variant::Yenc => Self::encode_yenc(bytes),
+            Variant::BinHex => Self::encode_binhex(bytes),
+        }
+    }
+
+    ///CRC-32 (IEEE 802.3) used by yEnc
+    fn crc32(bytes: & [u8]) -> u32 {
+        !bytes.iter().fold(!0u32, |crc, &byte| {
+            (0..8).fold(crc ^ byte as u32, |crc, _| {
+                if crc & 1 != 0 { (crc >> 1) ^ 0xEDB88320 } else { crc >> 1 }
+            })
+        })
+    }
+
+    ///CRC-16/XMODEM used by BinHex
+    fn crc16(bytes: & [u8]) -> u16 {
+        bytes.iter().fold(0u16, |crc, &byte| {
+            (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| {
+                if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 }
+            })
+        })
+    }
+
+    ///Uuencode and xxencode only differ by alphabet. Each line starts with the number of bytes on the line, followed by 4 characters for every 3 bytes
+    fn decode_uu(string: &str, decode_character: fn(u8) -> Option<u8>) -> Result<Vec<u8>, ()> {
+        lazy_static! {
+            static ref BEGIN: Regex = Regex::new("^begin [0-7]{3,4} .+$").unwrap();
+        }
+
+        let mut lines = string.lines().map(|line| line.trim_end()).skip_while(|line| line.is_empty());
+
+        if !BEGIN.is_match(lines.next().ok_or(())?) {
+            return Err(());
+        }
+
+        let mut bytes = Vec::new();
+
+        for line in lines {
+            if line == "end" {
+                return Ok(bytes);
+            }
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let line = line.as_bytes();
+
+            let count = decode_character(line[0]).ok_or(())? as usize;
+
+            //Some encoders strip trailing spaces, so missing characters are treated as zero
+            let mut digits = line[1..].iter()
+                .map(|&x| decode_character(x))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(())?;
+
+            let required = count.div_ceil(3) * 4;
+
+            if digits.len() < required {
+                digits.resize(required, 0);
+            }
+
+            let decoded: Vec<_> = digits[..required].chunks(4).flat_map(|group| {
+                vec![group[0] << 2 | group[1] >> 4, group[1] << 4 | group[2] >> 2, group[2] << 6 | group[3]]
+            }).collect();
+
+            bytes.extend_from_slice(&decoded[..count]);
+        }
+
+        //No 'end' line
+        Err(())
+    }
+
+    fn encode_uu(bytes: & [u8], encode_character: fn(u8) -> char) -> String {
+        let mut string = format!("begin 644 {}\n", FILE_NAME);
+
+        for line in bytes.chunks(UUENCODE_LINE_BYTES) {
+            string.push(encode_character(line.len() as u8));
+
+            for group in line.chunks(3) {
+                let mut padded = [0u8; 3];
+                padded[..group.len()].copy_from_slice(group);
+
+                string.push(encode_character(padded[0] >> 2));
+                string.push(encode_character((padded[0] << 4 | padded[1] >> 4) & 0x3F));
+                string.push(encode_character((padded[1] << 2 | padded[2] >> 6) & 0x3F));
+                string.push(encode_character(padded[2] & 0x3F));
+            }
+
+            string.push('\n');
+        }
+
+        //An empty line (a line with a length of zero) marks the end of the data
+        string.push(encode_character(0));
+        string.push_str("\nend");
+
+        string
+    }
+
+    ///yEnc data can contain any byte, so characters are interpreted as ISO-8859-1 (each character is one byte). Input that was read
+    ///as UTF-8 from a file in another charset can have characters above U+00FF, which are rejected rather than truncated
+    fn decode_yenc(string: &str) -> Result<Vec<u8>, ()> {
+        lazy_static! {
+            static ref SIZE: Regex = Regex::new("(?:^|\\s)size=(\\d+)").unwrap();
+            static ref CRC32: Regex = Regex::new("(?:^|\\s)crc32=([0-9a-fA-F]{1,8})").unwrap();
+        }
+
+        let mut lines = string.lines().skip_while(|line| line.trim_end().is_empty());
+
+        let header = lines.next().ok_or(())?;
+
+        if !header.starts_with("=ybegin ") {
+            return Err(());
+        }
+
+        let size = usize::from_str(SIZE.captures(header).ok_or(())?.get(1).unwrap().as_str()).map_err(|_| ())?;
+
+        let mut bytes = Vec::new();
+
+        for line in lines {
+            if line.starts_with("=yend") {
+                //The trailer may repeat the size and include a checksum
+                if let Some(captures) = SIZE.captures(line) {
+                    if usize::from_str(captures.get(1).unwrap().as_str()) != Ok(size) {
+                        return Err(());
+                    }
+                }
+
+                if let Some(captures) = CRC32.captures(line) {
+                    if u32::from_str_radix(captures.get(1).unwrap().as_str(), 16) != Ok(Self::crc32(&bytes)) {
+                        return Err(());
+                    }
+                }
+
+                return if bytes.len() == size { Ok(bytes) } else { Err(()) };
+            }
+
+            //Multi-part information is not needed to decode a single part
+            if line.starts_with("=ypart ") {
+                continue;
+            }
+
+            let mut escaped = false;
+
+            for character in line.chars() {
+                //Characters outside ISO-8859-1 can't be a byte of yEnc data
+                let byte: u8 = (character as u32).try_into().map_err(|_| ())?;
+
+                if byte == b'\r' {
+                    continue;
+                }
+
+                if byte == b'=' && !escaped {
+                    escaped = true;
+                    continue;
+                }
+
+                let byte = if escaped { byte.wrapping_sub(64) } else { byte };
+
+                bytes.push(byte.wrapping_sub(42));
+                escaped = false;
+            }
+        }
+
+        //No '=yend' line
+        Err(())
+    }
+
+    fn encode_yenc(bytes: & [u8]) -> String {
+        let mut string = format!("=ybegin line={} size={} name={}\n", YENC_LINE_LENGTH, bytes.len(), FILE_NAME);
+
+        let mut column = 0;
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            let encoded = byte.wrapping_add(42);
+
+            let is_line_start = column == 0;
+            let is_line_end = column + 1 >= YENC_LINE_LENGTH || i == bytes.len() - 1;
+
+            //NUL, LF, CR and '=' are always escaped. Tabs and spaces are escaped at the ends of lines, and '.' at the start
+            let escape = match encoded {
+                0x00 | 0x0A | 0x0D | 0x3D => true,
+                0x09 | 0x20 => is_line_start || is_line_end,
+                0x2E => is_line_start,
+                _ => false,
+            };
+
+            if escape {
+                string.push('=');
+                string.push(encoded.wrapping_add(64) as char);
+                column += 2;
+            } else {
+                string.push(encoded as char);
+                column += 1;
+            }
+
+            if column >= YENC_LINE_LENGTH {
+                string.push('\n');
+                column = 0;
+            }
+        }
+
+        if column != 0 {
+            string.push('\n');
+        }
+
+        string.push_str(&format!("=yend size={} crc32={:08x}", bytes.len(), Self::crc32(bytes)));
+
+        string
+    }
+
+    ///BinHex 4.0 decodes to a header, the data fork and the resource fork. Only the data fork is kept
+    fn decode_binhex(string: &str) -> Result<Vec<u8>, ()> {
+        let start = string.find(BINHEX_HEADER).ok_or(())? + BINHEX_HEADER.len();
+
+        let encoded: Vec<_> = string[start..].bytes().filter(|x| !x.is_ascii_whitespace()).collect();
+
+        //The data is enclosed in colons
+        if encoded.len() < 2 || encoded[0] != b':' || encoded[encoded.len() - 1] != b':' {
+            return Err(());
+        }
+
+        let digits = encoded[1..encoded.len() - 1].iter()
+            .map(|&x| BINHEX_ALPHABET.iter().position(|&y| y == x).map(|y| y as u8))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(())?;
+
+        let mut compressed = Vec::new();
+
+        for group in digits.chunks(4) {
+            let bits = group.iter().fold(0u32, |acc, &x| acc << 6 | x as u32) << (6 * (4 - group.len()));
+
+            //A partial group only contains whole bytes, any remaining bits are ignored
+            compressed.extend_from_slice(&bits.to_be_bytes()[1..1 + group.len() * 6 / 8]);
+        }
+
+        let stream = Self::rle_decode(&compressed)?;
+
+        let take = |offset: &mut usize, length: usize| -> Result<& [u8], ()> {
+            let slice = stream.get(*offset..*offset + length).ok_or(())?;
+            *offset += length;
+            Ok(slice)
+        };
+
+        let mut offset = 0;
+
+        let name_length = take(&mut offset, 1)?[0] as usize;
+
+        //Name, version, type, creator and flags
+        take(&mut offset, name_length + 1 + 4 + 4 + 2)?;
+
+        let data_length = u32::from_be_bytes(take(&mut offset, 4)?.try_into().unwrap()) as usize;
+        let resource_length = u32::from_be_bytes(take(&mut offset, 4)?.try_into().unwrap()) as usize;
+
+        let header_end = offset;
+
+        let header_crc = u16::from_be_bytes(take(&mut offset, 2)?.try_into().unwrap());
+
+        if Self::crc16(&stream[..header_end]) != header_crc {
+            return Err(());
+        }
+
+        let data = take(&mut offset, data_length)?;
+
+        if Self::crc16(data) != u16::from_be_bytes(take(&mut offset, 2)?.try_into().unwrap()) {
+            return Err(());
+        }
+
+        let resource = take(&mut offset, resource_length)?;
+
+        if Self::crc16(resource) != u16::from_be_bytes(take(&mut offset, 2)?.try_into().unwrap()) {
+            return Err(());
+        }
+
+        Ok(Vec::from(data))
+    }
+
+    fn encode_binhex(bytes: & [u8]) -> String {
+        let mut stream = vec![FILE_NAME.len() as u8];
+        stream.extend_from_slice(FILE_NAME.as_bytes());
+        stream.push(0);
+        stream.extend_from_slice(BINHEX_TYPE);
+        stream.extend_from_slice(BINHEX_CREATOR);
+        stream.extend_from_slice(&[0, 0]);
+        stream.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        stream.extend_from_slice(&0u32.to_be_bytes());
+
+        let header_crc = Self::crc16(&stream);
+        stream.extend_from_slice(&header_crc.to_be_bytes());
+
+        stream.extend_from_slice(bytes);
+        stream.extend_from_slice(&Self::crc16(bytes).to_be_bytes());
+
+        //Empty resource fork
+        stream.extend_from_slice(&Self::crc16(&[]).to_be_bytes());
+
+        let compressed = Self::rle_encode(&stream);
+
+        let mut encoded = String::from(":");
+
+        for group in compressed.chunks(3) {
+            let mut padded = [0u8; 4];
+            padded[1..1 + group.len()].copy_from_slice(group);
+
+            let bits = u32::from_be_bytes(padded);
+
+            for i in 0..(group.len() * 8).div_ceil(6) {
+                encoded.push(BINHEX_ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char);
+            }
+        }
+
+        encoded.push(':');
+
+        let mut string = format!("{}\n", BINHEX_HEADER);
+
+        let lines: Vec<_> = encoded.as_bytes().chunks(BINHEX_LINE_LENGTH).map(|line| String::from_utf8_lossy(line)).collect();
+
+        string.push_str(&lines.join("\n"));
+
+        string
+    }
+
+    ///BinHex run length encoding: 0x90 followed by a count repeats the previous byte, and 0x90 0x00 is a literal 0x90
+    fn rle_decode(bytes: & [u8]) -> Result<Vec<u8>, ()> {
+        let mut decoded: Vec<u8> = Vec::new();
+        let mut iter = bytes.iter();
+
+        while let Some(&byte) = iter.next() {
+            if byte == RLE_MARKER {
+                match *iter.next().ok_or(())? {
+                    0 => decoded.push(RLE_MARKER),
+                    count => {
+                        let previous = *decoded.last().ok_or(())?;
+
+                        for _ in 1..count {
+                            decoded.push(previous);
+                        }
+                    }
+                }
+            } else {
+                decoded.push(byte);
+            }
+        }
+
+        Ok(decoded)
+    }
+
+    fn rle_encode(bytes: & [u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+
+        let push_literal = |encoded: &mut Vec<u8>, byte: u8| {
+            encoded.push(byte);
+
+            if byte == RLE_MARKER {
+                encoded.push(0);
+            }
+        };
+
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let byte = bytes[i];
+
+            let mut run = 1;
+
+            while i + run < bytes.len() && bytes[i + run] == byte && run < 255 {
+                run += 1;
+            }
+
+            push_literal(&mut encoded, byte);
+
+            if run > 2 {
+                encoded.push(RLE_MARKER);
+                encoded.push(run as u8);
+            } else if run == 2 {
+                push_literal(&mut encoded, byte);
+            }
+
+            i += run;
+        }
+
+        encoded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uuencode_known_vector() {
+        //The 'Cat' example, 3 bytes written as a count character and 4 characters
+        let encoded = "begin 644 data.bin\n#0V%T\n`\nend";
+
+        assert_eq!(AttachmentEncoding::encode(b"Cat", Variant::Uuencode), encoded);
+        assert_eq!(AttachmentEncoding::decode(encoded, Variant::Uuencode), Ok(b"Cat".to_vec()));
+
+        //Trailing spaces stripped by some encoders are treated as zero
+        assert_eq!(AttachmentEncoding::decode("begin 644 a\n\"  \n`\nend", Variant::Uuencode), Ok(vec![0, 0]));
+        assert!(AttachmentEncoding::decode("begin 644 data.bin\n#0V%T\n`", Variant::Uuencode).is_err());
+    }
+
+    #[test]
+    fn xxencode_known_vector() {
+        let encoded = "begin 644 data.bin\n1Eq3o\n+\nend";
+
+        assert_eq!(AttachmentEncoding::encode(b"Cat", Variant::Xxencode), encoded);
+        assert_eq!(AttachmentEncoding::decode(encoded, Variant::Xxencode), Ok(b"Cat".to_vec()));
+    }
+
+    #[test]
+    fn crc_check_values() {
+        assert_eq!(AttachmentEncoding::crc32(b"123456789"), 0xCBF43926);
+        assert_eq!(AttachmentEncoding::crc16(b"123456789"), 0x31C3);
+    }
+
+    #[test]
+    fn yenc_crc32() {
+        let encoded = "=ybegin line=128 size=9 name=data.bin\n[\\]^_`abc\n=yend size=9 crc32=cbf43926";
+
+        assert_eq!(AttachmentEncoding::encode(b"123456789", Variant::Yenc), encoded);
+        assert_eq!(AttachmentEncoding::decode(encoded, Variant::Yenc), Ok(b"123456789".to_vec()));
+
+        assert!(AttachmentEncoding::decode("=ybegin line=128 size=9 name=data.bin\n[\\]^_`abc\n=yend size=9 crc32=cbf43927", Variant::Yenc).is_err());
+        assert!(AttachmentEncoding::decode("=ybegin line=128 size=8 name=data.bin\n[\\]^_`abc\n=yend", Variant::Yenc).is_err());
+    }
+
+    #[test]
+    fn yenc_escapes() {
+        //Bytes that encode to NUL and '=' are always escaped, and a space at the end of a line
+        assert_eq!(AttachmentEncoding::encode(&[0xD6, 0x13, 0xF6], Variant::Yenc).lines().nth(1), Some("=@=}=`"));
+
+        //Characters outside ISO-8859-1 can't be yEnc bytes
+        assert!(AttachmentEncoding::decode("=ybegin line=128 size=1 name=data.bin\n\u{263A}\n=yend", Variant::Yenc).is_err());
+    }
+
+    #[test]
+    fn binhex_rle() {
+        assert_eq!(AttachmentEncoding::rle_encode(&[1, 1, 1, 1, 1, 2, 2, RLE_MARKER]), vec![1, RLE_MARKER, 5, 2, 2, RLE_MARKER, 0]);
+        assert_eq!(AttachmentEncoding::rle_decode(&[1, RLE_MARKER, 5, 2, 2, RLE_MARKER, 0]), Ok(vec![1, 1, 1, 1, 1, 2, 2, RLE_MARKER]));
+
+        //A run of the marker itself, and a run with no previous byte
+        assert_eq!(AttachmentEncoding::rle_decode(&AttachmentEncoding::rle_encode(&[RLE_MARKER; 4])), Ok(vec![RLE_MARKER; 4]));
+        assert!(AttachmentEncoding::rle_decode(&[RLE_MARKER, 3]).is_err());
+    }
+
+    #[test]
+    fn binhex_crc() {
+        let encoded = AttachmentEncoding::encode(b"123456789", Variant::BinHex);
+
+        assert!(encoded.starts_with(BINHEX_HEADER));
+        assert_eq!(AttachmentEncoding::decode(&encoded, Variant::BinHex), Ok(b"123456789".to_vec()));
+
+        //Changing a character of the data breaks the data fork CRC
+        let corrupted = encoded.replacen("pM%b", "pM%c", 1);
+
+        assert_ne!(corrupted, encoded);
+        assert!(AttachmentEncoding::decode(&corrupted, Variant::BinHex).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..=255).chain([0; 300].iter().copied()).collect();
+
+        for variant in [Variant::Uuencode, Variant::Xxencode, Variant::Yenc, Variant::BinHex] {
+            for length in [0, 1, 2, 3, 44, 45, 46, 127, 128, 256, bytes.len()] {
+                let encoded = AttachmentEncoding::encode(&bytes[..length], variant.clone());
+
+                assert_eq!(AttachmentEncoding::decode(&encoded, variant.clone()), Ok(bytes[..length].to_vec()), "{:?} {}", variant, length);
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::str::FromStr;
+use std::convert::TryInto;
+
+/// base85.rs implements Ascii85 (raw, Adobe and btoa framing) and RFC 1924 base 85 encoding
+
+#[derive(Debug, Clone)]
+pub enum Variant {
+    Ascii85, //Raw Ascii85 with the 'z' shortcut for four zero bytes
+    Adobe, //Ascii85 wrapped in <~ and ~>
+    Btoa, //btoa 4.2, with 'xbtoa Begin' header, 'xbtoa End' trailer and the 'y' shortcut for four spaces
+    Rfc1924, //RFC 1924 encoding of 128-bit IPv6 addresses
+}
+
+const RFC1924_ALPHABET: & [u8; 85] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+//Maximum number of characters per line in btoa output
+const BTOA_LINE_LENGTH: usize = 78;
+
+pub struct Base85Encoding;
+
+impl Base85Encoding {
+    pub fn decode(string: &str, variant: Variant) -> Result<Vec<u8>, ()> {
+        match variant {
+            Variant::Ascii85 => Self::decode_ascii85(string, false),
+            Variant::Adobe => {
+                let trimmed = string.trim();
+
+                if trimmed.len() >= 4 && trimmed.starts_with("<~") && trimmed.ends_with("~>") {
+                    Self::decode_ascii85(&trimmed[2..trimmed.len() - 2], false)
+                } else {
+                    Err(())
+                }
+            },
+            Variant::Btoa => Self::decode_btoa(string),
+            Variant::Rfc1924 => Self::decode_rfc1924(string),
+        }
+    }
+
+    ///Encode the bytes. NOTE: RFC 1924 requires exactly 16 bytes and will panic otherwise
+    pub fn encode(bytes: & [u8], variant: Variant) -> String {
+        match variant {
+            Variant::Ascii85 => Self::encode_ascii85(bytes, false),
+            Variant::Adobe => format!("<~{}~>", Self::encode_ascii85(bytes, false)),
+            Variant::Btoa => Self::encode_btoa(bytes),
+            Variant::Rfc1924 => Self::encode_rfc1924(bytes),
+        }
+    }
+
+    ///Convert 5 base 85 digits into 4 bytes, failing if the group is larger than 32 bits
+    fn decode_group(digits: & [u8]) -> Result<[u8; 4], ()> {
+        let value = digits.iter().fold(0u64, |acc, &digit| acc * 85 + digit as u64);
+
+        if value > u32::MAX as u64 {
+            Err(())
+        } else {
+            Ok((value as u32).to_be_bytes())
+        }
+    }
+
+    ///Convert 4 bytes into 5 base 85 digits, using '!' as the zero digit
+    fn encode_group(group: [u8; 4]) -> [u8; 5] {
+        let mut value = u32::from_be_bytes(group);
+        let mut digits = [0u8; 5];
+
+        for digit in digits.iter_mut().rev() {
+            *digit = (value % 85) as u8 + b'!';
+            value /= 85;
+        }
+
+        digits
+    }
+
+    fn decode_ascii85(string: &str, allow_y: bool) -> Result<Vec<u8>, ()> {
+        let mut bytes = Vec::new();
+        let mut group = Vec::with_capacity(5);
+
+        //Whitespace is ignored anywhere in the encoded data
+        for character in string.bytes().filter(|x| !x.is_ascii_whitespace()) {
+            match character {
+                b'z' if group.is_empty() => bytes.extend_from_slice(&[0; 4]),
+                b'y' if allow_y && group.is_empty() => bytes.extend_from_slice(&[0x20; 4]),
+                b'!'..=b'u' => {
+                    group.push(character - b'!');
+
+                    if group.len() == 5 {
+                        bytes.extend_from_slice(&Self::decode_group(&group)?);
+                        group.clear();
+                    }
+                },
+                _ => return Err(()),
+            }
+        }
+
+        //A final group of n characters is padded with 'u' and represents n-1 bytes
+        match group.len() {
+            0 => {},
+            1 => return Err(()),
+            count => {
+                group.resize(5, 84);
+
+                bytes.extend_from_slice(&Self::decode_group(&group)?[..count - 1]);
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    fn encode_ascii85(bytes: & [u8], btoa: bool) -> String {
+        let mut string = String::new();
+
+        for chunk in bytes.chunks(4) {
+            let mut group = [0u8; 4];
+            group[..chunk.len()].copy_from_slice(chunk);
+
+            //btoa pads the final group with zeros and always writes 5 characters, whereas Ascii85 only writes n+1 characters
+            if group == [0; 4] && (btoa || chunk.len() == 4) {
+                string.push('z');
+            } else if btoa && group == [0x20; 4] {
+                string.push('y');
+            } else {
+                let count = if btoa { 5 } else { chunk.len() + 1 };
+
+                string.extend(Self::encode_group(group)[..count].iter().map(|&x| x as char));
+            }
+        }
+
+        string
+    }
+
+    ///Calculate the btoa xor, sum and rotation checksums. btoa includes the zero padding of the final group in the checksums
+    fn btoa_checksums(bytes: & [u8]) -> (u32, u32, u32) {
+        bytes.iter().fold((0u32, 0u32, 0u32), |(eor, sum, rot), &byte| {
+            let rot = if rot & 0x80000000 != 0 {
+                (rot << 1).wrapping_add(1)
+            } else {
+                rot << 1
+            };
+
+            (eor ^ byte as u32, sum.wrapping_add(byte as u32 + 1), rot.wrapping_add(byte as u32))
+        })
+    }
+
+    fn decode_btoa(string: &str) -> Result<Vec<u8>, ()> {
+        lazy_static! {
+            static ref BTOA: Regex = Regex::new("^\\s*xbtoa Begin\\s(?s:(.*))xbtoa End N (\\d+) ([0-9a-fA-F]+) E ([0-9a-fA-F]+) S ([0-9a-fA-F]+) R ([0-9a-fA-F]+)\\s*$").unwrap();
+        }
+
+        let captures = BTOA.captures(string).ok_or(())?;
+
+        let hex = |i: usize| u32::from_str_radix(captures.get(i).unwrap().as_str(), 16).map_err(|_| ());
+
+        let length = usize::from_str(captures.get(2).unwrap().as_str()).map_err(|_| ())?;
+
+        let mut bytes = Self::decode_ascii85(captures.get(1).unwrap().as_str(), true)?;
+
+        //The final group is padded to 4 bytes, so the trailer length must be within 3 bytes of the decoded length
+        if length > bytes.len() || bytes.len() - length > 3 || length as u32 != hex(3)? {
+            return Err(());
+        }
+
+        if Self::btoa_checksums(&bytes) != (hex(4)?, hex(5)?, hex(6)?) {
+            return Err(());
+        }
+
+        bytes.truncate(length);
+
+        Ok(bytes)
+    }
+
+    fn encode_btoa(bytes: & [u8]) -> String {
+        let body = Self::encode_ascii85(bytes, true);
+
+        let mut string = String::from("xbtoa Begin\n");
+
+        for line in body.as_bytes().chunks(BTOA_LINE_LENGTH) {
+            string.push_str(&String::from_utf8_lossy(line));
+            string.push('\n');
+        }
+
+        let mut padded = Vec::from(bytes);
+        padded.resize(bytes.len().div_ceil(4) * 4, 0);
+
+        let (eor, sum, rot) = Self::btoa_checksums(&padded);
+
+        string.push_str(&format!("xbtoa End N {} {:x} E {:x} S {:x} R {:x}", bytes.len(), bytes.len(), eor, sum, rot));
+
+        string
+    }
+
+    ///RFC 1924 treats the 16 bytes as a single little endian 128-bit number (matching `IpV6`) written with 20 digits
+    fn decode_rfc1924(string: &str) -> Result<Vec<u8>, ()> {
+        if string.len() != 20 {
+            return Err(());
+        }
+
+        let mut value: u128 = 0;
+
+        for character in string.bytes() {
+            let digit = RFC1924_ALPHABET.iter().position(|&x| x == character).ok_or(())?;
+
+            value = value.checked_mul(85)
+                .and_then(|x| x.checked_add(digit as u128))
+                .ok_or(())?;
+        }
+
+        Ok(Vec::from(value.to_le_bytes()))
+    }
+
+    fn encode_rfc1924(bytes: & [u8]) -> String {
+        let mut value = u128::from_le_bytes(bytes.try_into().unwrap());
+        let mut digits = [0u8; 20];
+
+        for digit in digits.iter_mut().rev() {
+            *digit = RFC1924_ALPHABET[(value % 85) as usize];
+            value /= 85;
+        }
+
+        digits.iter().map(|&x| x as char).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii85_known_vectors() {
+        assert_eq!(Base85Encoding::encode(b"Man is", Variant::Ascii85), "9jqo^Bla");
+        assert_eq!(Base85Encoding::decode("9jqo^Bla", Variant::Ascii85), Ok(b"Man is".to_vec()));
+        assert_eq!(Base85Encoding::decode("<~F*2M7/c~>", Variant::Adobe), Ok(b"sure.".to_vec()));
+
+        //Four zero bytes are written as 'z', but only as a whole group
+        assert_eq!(Base85Encoding::encode(&[0; 4], Variant::Ascii85), "z");
+        assert_eq!(Base85Encoding::decode("z", Variant::Ascii85), Ok(vec![0; 4]));
+        assert_eq!(Base85Encoding::encode(&[0; 3], Variant::Ascii85), "!!!!");
+    }
+
+    #[test]
+    fn ascii85_rejects_invalid_groups() {
+        //A final group of a single character, and a group larger than 32 bits
+        assert!(Base85Encoding::decode("9jqo^B", Variant::Ascii85).is_err());
+        assert!(Base85Encoding::decode("s8W-\"", Variant::Ascii85).is_err());
+        assert!(Base85Encoding::decode("F*2M7/c", Variant::Adobe).is_err());
+    }
+
+    #[test]
+    fn btoa_checksums() {
+        //The final group is padded to 'abc\0', giving an xor of 0x60, a sum of 0x12a and a rotation of 0x556
+        let encoded = "xbtoa Begin\n@:E^H\nxbtoa End N 3 3 E 60 S 12a R 556";
+
+        assert_eq!(Base85Encoding::encode(b"abc", Variant::Btoa), encoded);
+        assert_eq!(Base85Encoding::decode(encoded, Variant::Btoa), Ok(b"abc".to_vec()));
+
+        assert!(Base85Encoding::decode("xbtoa Begin\n@:E^H\nxbtoa End N 3 3 E 61 S 12a R 556", Variant::Btoa).is_err());
+        assert!(Base85Encoding::decode("xbtoa Begin\n@:E^H\nxbtoa End N 5 5 E 60 S 12a R 556", Variant::Btoa).is_err());
+    }
+
+    #[test]
+    fn btoa_shortcuts() {
+        let encoded = Base85Encoding::encode(&[0x20; 8], Variant::Btoa);
+
+        assert!(encoded.contains("\nyy\n"));
+        assert_eq!(Base85Encoding::decode(&encoded, Variant::Btoa), Ok(vec![0x20; 8]));
+    }
+
+    #[test]
+    fn rfc1924_known_vector() {
+        //1080:0:0:0:8:800:200C:417A from RFC 1924, with the bytes in the little endian order used by `IpV6`
+        let mut address = vec![0x10, 0x80, 0, 0, 0, 0, 0, 0, 0, 0x08, 0x08, 0x00, 0x20, 0x0C, 0x41, 0x7A];
+        address.reverse();
+
+        assert_eq!(Base85Encoding::encode(&address, Variant::Rfc1924), "4)+k&C#VzJ4br>0wv%Yp");
+        assert_eq!(Base85Encoding::decode("4)+k&C#VzJ4br>0wv%Yp", Variant::Rfc1924), Ok(address));
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..=255).chain([0, 0, 0, 0, 0x20, 0x20, 0x20, 0x20, 1]).collect();
+
+        for variant in [Variant::Ascii85, Variant::Adobe, Variant::Btoa] {
+            for length in 0..bytes.len() {
+                let encoded = Base85Encoding::encode(&bytes[..length], variant.clone());
+
+                assert_eq!(Base85Encoding::decode(&encoded, variant.clone()), Ok(bytes[..length].to_vec()), "{:?}", variant);
+            }
+        }
+    }
+}
@@ -0,0 +1,372 @@
+use encoding_rs::Encoding;
+use oem_cp::code_table::{DECODING_TABLE_CP437, ENCODING_TABLE_CP437};
+
+/// codepage.rs implements legacy single and multi-byte character sets, and guesses which of them a set of bytes uses
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Ebcdic(& 'static [char; 256]),
+    Latin1, //ISO-8859-1, which encoding_rs treats as Windows-1252
+    Cp437,
+    EncodingRs(& 'static str), //Label of a character set implemented by encoding_rs
+}
+
+#[derive(Debug)]
+pub struct CharacterSet {
+    pub name: & 'static str,
+    kind: Kind,
+}
+
+//Supported codepages. When several give the same result, the first one is used to name the result
+pub static CODEPAGES: [CharacterSet; 31] = [
+    CharacterSet { name: "CP037 EBCDIC", kind: Kind::Ebcdic(&CP037) },
+    CharacterSet { name: "CP500 EBCDIC", kind: Kind::Ebcdic(&CP500) },
+    CharacterSet { name: "CP1047 EBCDIC", kind: Kind::Ebcdic(&CP1047) },
+    CharacterSet { name: "Windows-1252", kind: Kind::EncodingRs("windows-1252") },
+    CharacterSet { name: "ISO-8859-1", kind: Kind::Latin1 },
+    CharacterSet { name: "ISO-8859-15", kind: Kind::EncodingRs("iso-8859-15") },
+    CharacterSet { name: "Windows-1250", kind: Kind::EncodingRs("windows-1250") },
+    CharacterSet { name: "ISO-8859-2", kind: Kind::EncodingRs("iso-8859-2") },
+    CharacterSet { name: "Windows-1251", kind: Kind::EncodingRs("windows-1251") },
+    CharacterSet { name: "KOI8-R", kind: Kind::EncodingRs("koi8-r") },
+    CharacterSet { name: "ISO-8859-5", kind: Kind::EncodingRs("iso-8859-5") },
+    CharacterSet { name: "Windows-1253", kind: Kind::EncodingRs("windows-1253") },
+    CharacterSet { name: "ISO-8859-7", kind: Kind::EncodingRs("iso-8859-7") },
+    CharacterSet { name: "Windows-1254", kind: Kind::EncodingRs("windows-1254") },
+    CharacterSet { name: "Windows-1255", kind: Kind::EncodingRs("windows-1255") },
+    CharacterSet { name: "ISO-8859-8", kind: Kind::EncodingRs("iso-8859-8") },
+    CharacterSet { name: "Windows-1256", kind: Kind::EncodingRs("windows-1256") },
+    CharacterSet { name: "ISO-8859-6", kind: Kind::EncodingRs("iso-8859-6") },
+    CharacterSet { name: "Windows-1257", kind: Kind::EncodingRs("windows-1257") },
+    CharacterSet { name: "ISO-8859-13", kind: Kind::EncodingRs("iso-8859-13") },
+    CharacterSet { name: "Windows-1258", kind: Kind::EncodingRs("windows-1258") },
+    CharacterSet { name: "ISO-8859-3", kind: Kind::EncodingRs("iso-8859-3") },
+    CharacterSet { name: "ISO-8859-4", kind: Kind::EncodingRs("iso-8859-4") },
+    CharacterSet { name: "ISO-8859-10", kind: Kind::EncodingRs("iso-8859-10") },
+    CharacterSet { name: "ISO-8859-14", kind: Kind::EncodingRs("iso-8859-14") },
+    CharacterSet { name: "ISO-8859-16", kind: Kind::EncodingRs("iso-8859-16") },
+    CharacterSet { name: "CP437", kind: Kind::Cp437 },
+    CharacterSet { name: "Shift_JIS", kind: Kind::EncodingRs("shift_jis") },
+    CharacterSet { name: "EUC-JP", kind: Kind::EncodingRs("euc-jp") },
+    CharacterSet { name: "GBK", kind: Kind::EncodingRs("gbk") },
+    CharacterSet { name: "Big5", kind: Kind::EncodingRs("big5") },
+];
+
+//Number of guesses given for bytes in an unknown codepage
+const GUESS_COUNT: usize = 5;
+
+//Lowest score for a guess to be shown
+const MINIMUM_SCORE: f64 = 0.5;
+
+//EBCDIC US and Canada
+const CP037: [char; 256] = [
+    '\u{00}', '\u{01}', '\u{02}', '\u{03}', '\u{9C}', '\u{09}', '\u{86}', '\u{7F}', '\u{97}', '\u{8D}', '\u{8E}', '\u{0B}', '\u{0C}', '\u{0D}', '\u{0E}', '\u{0F}',
+    '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{9D}', '\u{85}', '\u{08}', '\u{87}', '\u{18}', '\u{19}', '\u{92}', '\u{8F}', '\u{1C}', '\u{1D}', '\u{1E}', '\u{1F}',
+    '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{0A}', '\u{17}', '\u{1B}', '\u{88}', '\u{89}', '\u{8A}', '\u{8B}', '\u{8C}', '\u{05}', '\u{06}', '\u{07}',
+    '\u{90}', '\u{91}', '\u{16}', '\u{93}', '\u{94}', '\u{95}', '\u{96}', '\u{04}', '\u{98}', '\u{99}', '\u{9A}', '\u{9B}', '\u{14}', '\u{15}', '\u{9E}', '\u{1A}',
+    ' ', '\u{A0}', 'â', 'ä', 'à', 'á', 'ã', 'å', 'ç', 'ñ', '¢', '.', '<', '(', '+', '|',
+    '&', 'é', 'ê', 'ë', 'è', 'í', 'î', 'ï', 'ì', 'ß', '!', '$', '*', ')', ';', '¬',
+    '-', '/', 'Â', 'Ä', 'À', 'Á', 'Ã', 'Å', 'Ç', 'Ñ', '¦', ',', '%', '_', '>', '?',
+    'ø', 'É', 'Ê', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', '`', ':', '#', '@', '\'', '=', '"',
+    'Ø', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', '«', '»', 'ð', 'ý', 'þ', '±',
+    '°', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 'ª', 'º', 'æ', '¸', 'Æ', '¤',
+    'µ', '~', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '¡', '¿', 'Ð', 'Ý', 'Þ', '®',
+    '^', '£', '¥', '·', '©', '§', '¶', '¼', '½', '¾', '[', ']', '¯', '¨', '´', '×',
+    '{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', '\u{AD}', 'ô', 'ö', 'ò', 'ó', 'õ',
+    '}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', '¹', 'û', 'ü', 'ù', 'ú', 'ÿ',
+    '\\', '÷', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '²', 'Ô', 'Ö', 'Ò', 'Ó', 'Õ',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '³', 'Û', 'Ü', 'Ù', 'Ú', '\u{9F}',
+];
+
+//EBCDIC International
+const CP500: [char; 256] = [
+    '\u{00}', '\u{01}', '\u{02}', '\u{03}', '\u{9C}', '\u{09}', '\u{86}', '\u{7F}', '\u{97}', '\u{8D}', '\u{8E}', '\u{0B}', '\u{0C}', '\u{0D}', '\u{0E}', '\u{0F}',
+    '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{9D}', '\u{85}', '\u{08}', '\u{87}', '\u{18}', '\u{19}', '\u{92}', '\u{8F}', '\u{1C}', '\u{1D}', '\u{1E}', '\u{1F}',
+    '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{0A}', '\u{17}', '\u{1B}', '\u{88}', '\u{89}', '\u{8A}', '\u{8B}', '\u{8C}', '\u{05}', '\u{06}', '\u{07}',
+    '\u{90}', '\u{91}', '\u{16}', '\u{93}', '\u{94}', '\u{95}', '\u{96}', '\u{04}', '\u{98}', '\u{99}', '\u{9A}', '\u{9B}', '\u{14}', '\u{15}', '\u{9E}', '\u{1A}',
+    ' ', '\u{A0}', 'â', 'ä', 'à', 'á', 'ã', 'å', 'ç', 'ñ', '[', '.', '<', '(', '+', '!',
+    '&', 'é', 'ê', 'ë', 'è', 'í', 'î', 'ï', 'ì', 'ß', ']', '$', '*', ')', ';', '^',
+    '-', '/', 'Â', 'Ä', 'À', 'Á', 'Ã', 'Å', 'Ç', 'Ñ', '¦', ',', '%', '_', '>', '?',
+    'ø', 'É', 'Ê', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', '`', ':', '#', '@', '\'', '=', '"',
+    'Ø', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', '«', '»', 'ð', 'ý', 'þ', '±',
+    '°', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 'ª', 'º', 'æ', '¸', 'Æ', '¤',
+    'µ', '~', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '¡', '¿', 'Ð', 'Ý', 'Þ', '®',
+    '¢', '£', '¥', '·', '©', '§', '¶', '¼', '½', '¾', '¬', '|', '¯', '¨', '´', '×',
+    '{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', '\u{AD}', 'ô', 'ö', 'ò', 'ó', 'õ',
+    '}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', '¹', 'û', 'ü', 'ù', 'ú', 'ÿ',
+    '\\', '÷', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '²', 'Ô', 'Ö', 'Ò', 'Ó', 'Õ',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '³', 'Û', 'Ü', 'Ù', 'Ú', '\u{9F}',
+];
+
+//EBCDIC Latin-1 used by z/OS, which moves ^ ¬ [ ] Ý and ¨ compared to CP037
+const CP1047: [char; 256] = [
+    '\u{00}', '\u{01}', '\u{02}', '\u{03}', '\u{9C}', '\u{09}', '\u{86}', '\u{7F}', '\u{97}', '\u{8D}', '\u{8E}', '\u{0B}', '\u{0C}', '\u{0D}', '\u{0E}', '\u{0F}',
+    '\u{10}', '\u{11}', '\u{12}', '\u{13}', '\u{9D}', '\u{85}', '\u{08}', '\u{87}', '\u{18}', '\u{19}', '\u{92}', '\u{8F}', '\u{1C}', '\u{1D}', '\u{1E}', '\u{1F}',
+    '\u{80}', '\u{81}', '\u{82}', '\u{83}', '\u{84}', '\u{0A}', '\u{17}', '\u{1B}', '\u{88}', '\u{89}', '\u{8A}', '\u{8B}', '\u{8C}', '\u{05}', '\u{06}', '\u{07}',
+    '\u{90}', '\u{91}', '\u{16}', '\u{93}', '\u{94}', '\u{95}', '\u{96}', '\u{04}', '\u{98}', '\u{99}', '\u{9A}', '\u{9B}', '\u{14}', '\u{15}', '\u{9E}', '\u{1A}',
+    ' ', '\u{A0}', 'â', 'ä', 'à', 'á', 'ã', 'å', 'ç', 'ñ', '¢', '.', '<', '(', '+', '|',
+    '&', 'é', 'ê', 'ë', 'è', 'í', 'î', 'ï', 'ì', 'ß', '!', '$', '*', ')', ';', '^',
+    '-', '/', 'Â', 'Ä', 'À', 'Á', 'Ã', 'Å', 'Ç', 'Ñ', '¦', ',', '%', '_', '>', '?',
+    'ø', 'É', 'Ê', 'Ë', 'È', 'Í', 'Î', 'Ï', 'Ì', '`', ':', '#', '@', '\'', '=', '"',
+    'Ø', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', '«', '»', 'ð', 'ý', 'þ', '±',
+    '°', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 'ª', 'º', 'æ', '¸', 'Æ', '¤',
+    'µ', '~', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', '¡', '¿', 'Ð', '[', 'Þ', '®',
+    '¬', '£', '¥', '·', '©', '§', '¶', '¼', '½', '¾', 'Ý', '¨', '¯', ']', '´', '×',
+    '{', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', '\u{AD}', 'ô', 'ö', 'ò', 'ó', 'õ',
+    '}', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', '¹', 'û', 'ü', 'ù', 'ú', 'ÿ',
+    '\\', '÷', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '²', 'Ô', 'Ö', 'Ò', 'Ó', 'Õ',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '³', 'Û', 'Ü', 'Ù', 'Ú', '\u{9F}',
+];
+
+impl CharacterSet {
+    ///Find a codepage by name, ignoring anything after the name such as the list of other codepages in a variant
+    pub fn find(name: &str) -> Option<& 'static CharacterSet> {
+        let name = name.split(" (").next().unwrap();
+
+        CODEPAGES.iter().find(|codepage| codepage.name == name)
+    }
+
+    ///Returns true if the codepage writes ASCII as ASCII, which is true for everything but EBCDIC
+    pub fn is_ascii_compatible(&self) -> bool {
+        !matches!(self.kind, Kind::Ebcdic(_))
+    }
+
+    pub fn decode(&self, bytes: & [u8]) -> Result<String, ()> {
+        match self.kind {
+            Kind::Ebcdic(table) => Ok(bytes.iter().map(|&byte| table[byte as usize]).collect()),
+            Kind::Latin1 => Ok(bytes.iter().map(|&byte| byte as char).collect()),
+            Kind::Cp437 => Ok(bytes.iter().map(|&byte| if byte < 0x80 { byte as char } else { DECODING_TABLE_CP437[byte as usize - 0x80] }).collect()),
+            Kind::EncodingRs(label) => Encoding::for_label(label.as_bytes()).unwrap()
+                .decode_without_bom_handling_and_without_replacement(bytes)
+                .map(|text| text.into_owned())
+                .ok_or(()),
+        }
+    }
+
+    ///Encode the string, failing if any character is not in the codepage
+    pub fn encode(&self, string: &str) -> Result<Vec<u8>, ()> {
+        match self.kind {
+            Kind::Ebcdic(table) => string.chars()
+                .map(|character| table.iter().position(|&x| x == character).map(|x| x as u8).ok_or(()))
+                .collect(),
+            Kind::Latin1 => string.chars()
+                .map(|character| if (character as u32) < 0x100 { Ok(character as u8) } else { Err(()) })
+                .collect(),
+            Kind::Cp437 => oem_cp::encode_string_checked(string, &ENCODING_TABLE_CP437).ok_or(()),
+            Kind::EncodingRs(label) => {
+                let (bytes, _, had_errors) = Encoding::for_label(label.as_bytes()).unwrap().encode(string);
+
+                if had_errors {
+                    Err(())
+                } else {
+                    Ok(bytes.into_owned())
+                }
+            },
+        }
+    }
+
+    ///Name a result shared by several codepages, such as 'Windows-1252 (also ISO-8859-1 and ISO-8859-15)'
+    pub fn describe(names: & [& 'static str]) -> String {
+        match names {
+            [] => String::new(),
+            [name] => String::from(*name),
+            [name, other] => format!("{} (also {})", name, other),
+            [name, others @ .., last] if others.len() <= 2 => format!("{} (also {} and {})", name, others.join(", "), last),
+            [name, first, second, rest @ ..] => format!("{} (also {}, {} and {} more)", name, first, second, rest.len()),
+        }
+    }
+
+    ///Group codepages that give the same result, keeping the order of `CODEPAGES`
+    pub fn group<T: PartialEq>(results: impl Iterator<Item = (& 'static str, T)>) -> Vec<(Vec<& 'static str>, T)> {
+        let mut groups: Vec<(Vec<& 'static str>, T)> = Vec::new();
+
+        for (name, result) in results {
+            match groups.iter_mut().find(|(_, x)| *x == result) {
+                Some((names, _)) => names.push(name),
+                None => groups.push((vec![name], result)),
+            }
+        }
+
+        groups
+    }
+
+    ///Score how much the text looks like real text, from 1 for only letters, digits, whitespace and punctuation down to negative for control characters.
+    ///The wrong codepage tends to give accented Latin letters, letters mixed with digits or other scripts and uppercase letters following lowercase letters, so these score less
+    fn score(text: &str) -> f64 {
+        let mut total = 0;
+        let mut count = 0;
+        let mut previous = ' ';
+
+        for character in text.chars() {
+            let is_mixed = (character.is_alphabetic() && previous.is_numeric())
+                || (character.is_numeric() && previous.is_alphabetic())
+                || (character.is_alphabetic() && previous.is_alphabetic() && Self::script(character) != Self::script(previous));
+
+            total += if character.is_uppercase() && previous.is_lowercase() {
+                -2
+            } else if is_mixed {
+                0
+            } else if ('\u{C0}'..='\u{24F}').contains(&character) && character.is_alphabetic() {
+                1
+            } else if character.is_alphanumeric() || character.is_ascii_punctuation() || [' ', '\t', '\r', '\n'].contains(&character) {
+                2
+            } else if character.is_control() || ('\u{E000}'..='\u{F8FF}').contains(&character) {
+                -4
+            } else {
+                0
+            };
+
+            count += 2;
+            previous = character;
+        }
+
+        if count == 0 {
+            0.0
+        } else {
+            total as f64 / count as f64
+        }
+    }
+
+    ///Rough script of a letter, as Latin, CJK or otherwise the 256 character block it is in
+    fn script(character: char) -> u32 {
+        match character as u32 {
+            0..=0x24F => 0,
+            0x2E80.. => 1,
+            code_point => code_point >> 8,
+        }
+    }
+
+    ///Decode the bytes with every codepage that can, grouping codepages that give the same text
+    fn decode_all(bytes: & [u8]) -> Vec<(Vec<& 'static str>, String)> {
+        let results = CODEPAGES.iter().filter_map(|codepage| codepage.decode(bytes).ok().map(|text| (codepage.name, text)));
+
+        Self::group(results)
+    }
+
+    ///Every codepage that can decode the bytes, grouped by text, with the guesses first and the rest in the order of `CODEPAGES`
+    pub fn ranked(bytes: & [u8]) -> Vec<(Vec<& 'static str>, String)> {
+        let groups = Self::decode_all(bytes);
+        let mut ranked = Self::rank(groups.clone());
+
+        for group in groups {
+            if !ranked.contains(&group) {
+                ranked.push(group);
+            }
+        }
+
+        ranked
+    }
+
+    ///Guess the codepage from the decoded texts, sorting them by how much they look like real text and keeping the best few that are likely
+    ///enough to be shown
+    fn rank(groups: Vec<(Vec<& 'static str>, String)>) -> Vec<(Vec<& 'static str>, String)> {
+        let mut groups: Vec<_> = groups.into_iter()
+            .map(|(names, text)| (Self::score(&text), names, text))
+            .filter(|(score, _, _)| *score >= MINIMUM_SCORE)
+            .collect();
+
+        //The sort is stable, so equal scores keep the order of `CODEPAGES`
+        groups.sort_by(|(a, _, _), (b, _, _)| b.partial_cmp(a).unwrap());
+
+        groups.into_iter().take(GUESS_COUNT).map(|(_, names, text)| (names, text)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codepage(name: &str) -> & 'static CharacterSet {
+        CharacterSet::find(name).unwrap()
+    }
+
+    #[test]
+    fn known_vectors() {
+        assert_eq!(codepage("CP037 EBCDIC").encode("Hello"), Ok(vec![0xC8, 0x85, 0x93, 0x93, 0x96]));
+        assert_eq!(codepage("CP1047 EBCDIC").decode(&[0xAD, 0xBD]), Ok(String::from("[]")));
+        assert_eq!(codepage("Windows-1252").decode(&[0x80]), Ok(String::from("\u{20AC}")));
+        assert_eq!(codepage("ISO-8859-1").decode(&[0x80]), Ok(String::from("\u{80}")));
+        assert_eq!(codepage("KOI8-R").encode("Привет"), Ok(vec![0xF0, 0xD2, 0xC9, 0xD7, 0xC5, 0xD4]));
+        assert_eq!(codepage("CP437").decode(&[0xB0, 0xDB]), Ok(String::from("░█")));
+        assert_eq!(codepage("Shift_JIS").encode("日本"), Ok(vec![0x93, 0xFA, 0x96, 0x7B]));
+    }
+
+    #[test]
+    fn unencodable_characters() {
+        assert!(codepage("ISO-8859-1").encode("\u{20AC}").is_err());
+        assert!(codepage("KOI8-R").encode("日本").is_err());
+        assert!(codepage("CP037 EBCDIC").encode("\u{20AC}").is_err());
+
+        //Shift_JIS has no character for a lone lead byte
+        assert!(codepage("Shift_JIS").decode(&[0x93]).is_err());
+    }
+
+    #[test]
+    fn find_ignores_other_names() {
+        assert_eq!(codepage("Windows-1252 (also ISO-8859-1 and ISO-8859-15)").name, "Windows-1252");
+        assert!(CharacterSet::find("UTF-8").is_none());
+        assert!(!codepage("CP500 EBCDIC").is_ascii_compatible());
+    }
+
+    #[test]
+    fn describe_and_group() {
+        assert_eq!(CharacterSet::describe(&["A"]), "A");
+        assert_eq!(CharacterSet::describe(&["A", "B"]), "A (also B)");
+        assert_eq!(CharacterSet::describe(&["A", "B", "C", "D"]), "A (also B, C and D)");
+        assert_eq!(CharacterSet::describe(&["A", "B", "C", "D", "E"]), "A (also B, C and 2 more)");
+
+        let groups = CharacterSet::group(vec![("A", 1), ("B", 2), ("C", 1)].into_iter());
+
+        assert_eq!(groups, vec![(vec!["A", "C"], 1), (vec!["B"], 2)]);
+    }
+
+    #[test]
+    fn guess() {
+        let text = "Съешь же ещё этих мягких французских булок, да выпей чаю";
+
+        let bytes = codepage("KOI8-R").encode(text).unwrap();
+        let guesses = CharacterSet::rank(CharacterSet::decode_all(&bytes));
+
+        assert!(guesses.len() <= GUESS_COUNT);
+        assert!(guesses.iter().any(|(names, guess)| names.contains(&"KOI8-R") && guess == text));
+
+        //Every codepage that decodes the bytes is offered, with the guesses first
+        let ranked = CharacterSet::ranked(&bytes);
+
+        assert_eq!(ranked[..guesses.len()], guesses[..]);
+        assert!(ranked.len() > guesses.len());
+        assert!(ranked.iter().any(|(names, _)| names.contains(&"CP037 EBCDIC")));
+    }
+
+    #[test]
+    fn ranked_includes_ascii() {
+        let ranked = CharacterSet::ranked(b"hello");
+
+        //Codepages that keep ASCII are grouped together, ahead of EBCDIC
+        assert_eq!(ranked[0].0[0], "Windows-1252");
+        assert!(ranked[0].0.contains(&"KOI8-R"));
+        assert_eq!(ranked[0].1, "hello");
+        assert!(ranked.iter().any(|(names, _)| names.contains(&"CP500 EBCDIC")));
+    }
+
+    #[test]
+    fn round_trip() {
+        for codepage in CODEPAGES.iter() {
+            let text = "The quick brown fox, 0123456789";
+
+            let bytes = codepage.encode(text).unwrap();
+
+            assert_eq!(codepage.is_ascii_compatible(), bytes == text.as_bytes(), "{}", codepage.name);
+            assert_eq!(codepage.decode(&bytes), Ok(String::from(text)), "{}", codepage.name);
+        }
+
+        //Every byte of a single byte codepage decodes to a different character
+        for name in ["CP037 EBCDIC", "CP500 EBCDIC", "CP1047 EBCDIC", "ISO-8859-1", "CP437"] {
+            let bytes: Vec<u8> = (0..=255).collect();
+
+            let text = codepage(name).decode(&bytes).unwrap();
+
+            assert_eq!(codepage(name).encode(&text), Ok(bytes), "{}", name);
+        }
+    }
+}
@@ -1,40 +1,76 @@
 use std::str::FromStr;
+use std::borrow::Cow;
 use std::convert::TryInto;
 use crate::endian::Endianness;
 use crate::toir::ToIR;
+use lazy_static::lazy_static;
 
 //Convertable types
-pub struct Base2_16; //All numbers between base 2 and 16, each base implemented as a variant
+pub struct Base2_16; //All numbers between base 2 and 36, or up to base 64 with a custom alphabet, each base implemented as a variant
 pub struct FixedFloat; //16, 32, 64-bit floats
 pub struct DateTime; //32 and 64-bit unix time
 pub struct FixedInt; //8-128 bit signed/unsigned integer
-pub struct Unicode8;
+pub struct Unicode8; //UTF-8, CESU-8 and Modified UTF-8
+pub struct Unicode16; //UTF-16, with the byte order chosen like other dual endian types
+pub struct Unicode32; //UTF-32, with the byte order chosen like other dual endian types
+pub struct Normalization; //NFC, NFD, NFKC, NFKD and full case folding of text
+pub struct Codepage; //EBCDIC, ISO-8859, Windows, DOS, KOI8 and CJK character sets, each codepage implemented as a variant
 pub struct IpV4;
 pub struct IpV6;
 pub struct Base64;
 pub struct Base85;
 pub struct Base91;
+pub struct Base58; //Bitcoin, Ripple and Flickr alphabets, with and without Base58Check
+pub struct Base45; //RFC 9285
+pub struct Base62; //0-9A-Za-z, arbitrary length or fixed width KSUID
+pub struct Attachment; //uuencode, xxencode, yEnc and BinHex 4.0
+pub struct Mime; //Quoted-printable and RFC 2047 encoded-words
+pub struct Pem; //RFC 7468 armor, reporting the label and any RFC 1421 headers
+pub struct DataUri; //RFC 2397, keeping the media type
 pub struct ByteList;
 pub struct UUID;
 pub struct EscapedString;
+pub struct StringLiteral; //Quoted literals from source code, with prefixes, raw strings and concatenation
+pub struct HtmlEntities; //HTML5 named and numeric character references, and XML's 5 predefined entities
+pub struct RegexLiteral; //PCRE, POSIX ERE and Rust regex
+pub struct SqlString; //ANSI, MySQL and X'..' hex literals
+pub struct ShellWord; //POSIX shell quoting
 pub struct UrlEncode;
 pub struct UrlDecode;
+pub struct UrlComponents; //Scheme, userinfo, host, port, path segments, query parameters and fragment
+pub struct Punycode; //Raw RFC 3492 Punycode for a single label
+pub struct Idna; //Domains with xn-- labels
 pub struct UnicodeNames;
+pub struct CodePoints; //U+XXXX, HTML character references, \u{..}, \uXXXX (with surrogate pairs), \UXXXXXXXX, %uXXXX and 'c'
 pub struct Colour;
 
+lazy_static! {
+    //Variant names for each base, so that a base can be chosen at runtime and still give a `& 'static str`
+    static ref BASE_NAMES: Vec<String> = (0..=36).map(|base| format!("Base {}", base)).collect();
+}
+
 impl FixedInt {
     pub fn get_base2_16_variant(value: &str) -> Option<Variant> {
         if let Some(variants) = Base2_16::identify(value) {
 
+            //Primitive integers are only converted from bases 2, 8, 10 and 16. Bases that decode to the same bytes are grouped, so
+            //each name in a group is checked
+            let has_base = |variant: & Variant, base: & str| variant.0.split(", ").any(|name| name == base);
+
+            let variants: Vec<_> = variants.into_iter()
+                .filter(|variant| ["Base 2", "Base 8", "Base 10", "Base 16"].iter().any(|base| has_base(variant, base)))
+                .collect();
+
+            if variants.is_empty() {
+                return None;
+            }
+
             if variants.len() == 1 {
                 Some(variants.get(0).unwrap().clone())
             } else {
-                let b10 = Variant("Base 10");
-
-                if variants.contains(&b10) {
-                    Some(b10)
-                } else {
-                    Some(variants.last().unwrap().clone())
+                match variants.iter().find(|variant| has_base(variant, "Base 10")) {
+                    Some(b10) => Some(b10.clone()),
+                    None => Some(variants.last().unwrap().clone()),
                 }
             }
 
@@ -119,10 +155,13 @@ impl FixedFloat {
 }
 
 impl Base2_16 {
+    ///Bases that are always listed when they're valid for the input, as well as the smallest valid base
+    pub const COMMON_BASES: [u64; 5] = [2, 8, 10, 16, 36];
+
     pub fn ascii_to_num(byte: u8) -> u8 {
         if byte >= '0' as u8 && byte <= '9' as u8 {
             byte - ('0' as u8)
-        } else if byte >= 'a' as u8 && byte <= 'f' as u8 {
+        } else if byte >= 'a' as u8 && byte <= 'z' as u8 {
             byte - ('a' as u8) + 10
         } else {
             byte - ('A' as u8) + 10
@@ -149,10 +188,159 @@ impl Base2_16 {
         byte >= '0' as u8 && byte <= '7' as u8
     }
 
+    ///Get the base of the variant. For grouped variants such as "Base 8, Base 10" this is the first base
     pub fn get_base(variant: & Variant) -> u64 {
-        u64::from_str(&(variant.0)[5..]).unwrap()
+        u64::from_str((variant.0)[5..].split(|x: char| !x.is_ascii_digit()).next().unwrap()).unwrap()
+    }
+
+    ///Returns true if the variant uses an alphabet given with `--alphabet` instead of 0-9a-z
+    pub fn is_custom(variant: & Variant) -> bool {
+        variant.0.split(' ').nth(2) == Some("alphabet")
+    }
+
+    ///Returns true if the variant keeps leading zero bytes, with each digit representing a fixed number of bits
+    pub fn is_padded(variant: & Variant) -> bool {
+        !Self::is_custom(variant) && variant.0.split(", ").next().unwrap().ends_with(" padded")
+    }
+
+    ///Get the name of the padded variant for bases where each digit is a whole number of bits
+    pub fn padded_name(base: u64) -> Option<& 'static str> {
+        match base {
+            2 => Some("Base 2 padded"),
+            8 => Some("Base 8 padded"),
+            16 => Some("Base 16 padded"),
+            _ => None,
+        }
+    }
+
+    ///Number of bits represented by a single digit of a padded base, or None for bases that can't be padded
+    pub fn bits_per_digit(base: u64) -> Option<u64> {
+        match base {
+            2 => Some(1),
+            8 => Some(3),
+            16 => Some(4),
+            _ => None,
+        }
+    }
+
+    ///Get the name of the variant for a base between 2 and 36 using the 0-9a-z digits
+    pub fn base_name(base: u64) -> & 'static str {
+        BASE_NAMES[base as usize].as_str()
+    }
+
+    ///Check an alphabet given with `--alphabet`. The base is the length of the alphabet, so it must have between 2 and 64 unique characters
+    pub fn parse_alphabet(alphabet: & str) -> Result<Vec<char>, ()> {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+
+        let is_unique = alphabet.iter().enumerate().all(|(i, character)| !alphabet[..i].contains(character));
+
+        if alphabet.len() < 2 || alphabet.len() > 64 || !is_unique {
+            return Err(());
+        }
+
+        Ok(alphabet)
+    }
+
+    ///Get the variant for a custom alphabet. The alphabet is kept at the end of the name, so it can be read back when decoding and encoding
+    pub fn custom_variant(alphabet: & [char]) -> Variant {
+        Variant::from_string(format!("Base {} alphabet {}", alphabet.len(), alphabet.iter().collect::<String>()))
+    }
+
+    ///Get the alphabet of a custom alphabet variant
+    pub fn custom_alphabet(variant: & Variant) -> Vec<char> {
+        variant.0.splitn(4, ' ').nth(3).unwrap().chars().collect()
+    }
+
+    ///Get the custom alphabet variant if every character of the value is in the alphabet
+    pub fn identify_custom(value: & str, alphabet: & [char]) -> Option<Variant> {
+        if !value.is_empty() && value.chars().all(|character| alphabet.contains(&character)) {
+            Some(Self::custom_variant(alphabet))
+        } else {
+            None
+        }
+    }
+}
+
+impl Base58 {
+    ///Get the alphabet used by a Base58 variant, based on the first word of the variant name
+    pub fn get_alphabet(variant: & Variant) -> & 'static bs58::Alphabet {
+        match variant.0.split(' ').next().unwrap() {
+            "Bitcoin" => bs58::Alphabet::BITCOIN,
+            "Ripple" => bs58::Alphabet::RIPPLE,
+            "Flickr" => bs58::Alphabet::FLICKR,
+            _ => panic!("Invalid alphabet in Base58 variant"),
+        }
+    }
+
+    ///Returns true if the variant uses Base58Check (4-byte double SHA-256 checksum)
+    pub fn is_check(variant: & Variant) -> bool {
+        variant.0.ends_with(" Check")
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Variant(pub & 'static str);
+pub struct Variant(pub Cow<'static, str>);
+
+impl Variant {
+    pub const fn new(name: & 'static str) -> Variant {
+        Variant(Cow::Borrowed(name))
+    }
+
+    ///Create a variant from a name only known at runtime, such as a PEM label
+    pub fn from_string(name: String) -> Variant {
+        Variant(Cow::Owned(name))
+    }
+
+    ///Variants for the names that decoded, with names that gave the same bytes grouped together, such as "Rust, JSON". The first
+    ///name in a group is used to decode
+    pub fn grouped(decoded: Vec<(& 'static str, Vec<u8>)>) -> Vec<Variant> {
+        let mut groups: Vec<(Vec<u8>, Vec<& 'static str>)> = Vec::new();
+
+        for (name, bytes) in decoded {
+            match groups.iter_mut().find(|(group, _)| *group == bytes) {
+                Some((_, names)) => names.push(name),
+                None => groups.push((bytes, vec![name])),
+            }
+        }
+
+        groups.into_iter()
+            .map(|(_, names)| if names.len() == 1 { Variant::new(names[0]) } else { Variant::from_string(names.join(", ")) })
+            .collect()
+    }
+}
+
+impl Pem {
+    ///Label used when the input was not a PEM document
+    pub const DEFAULT_LABEL: & 'static str = "DATA";
+
+    ///Describe the document by its label followed by any headers
+    pub fn describe(document: & crate::armor::PemDocument) -> String {
+        if document.headers.is_empty() {
+            document.label.clone()
+        } else {
+            let headers: Vec<_> = document.headers.iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+
+            format!("{} ({})", document.label, headers.join(", "))
+        }
+    }
+
+    ///PEM output for a PEM document, which keeps the label from the variant of the input
+    pub fn variants_for_input(_ir: & [u8], input: Variant) -> Option<Vec<Variant>> {
+        Some(vec![Variant::from_string(input.0.split(" (").next().unwrap().to_string())])
+    }
+}
+
+impl DataUri {
+    ///Media type used when the input was not a data URI
+    pub const DEFAULT_MEDIA_TYPE: & 'static str = "application/octet-stream";
+
+    ///Variants for each encoding of a data URI with the media type
+    pub fn encodings(media_type: &str) -> Vec<Variant> {
+        vec![Variant::from_string(format!("{}, base64", media_type)), Variant::from_string(format!("{}, percent-encoded", media_type))]
+    }
+
+    ///Data URI outputs for a data URI, which keep the media type from the variant of the input
+    pub fn variants_for_input(_ir: & [u8], input: Variant) -> Option<Vec<Variant>> {
+        Some(Self::encodings(input.0.rsplit_once(", ").unwrap().0))
+    }
+}
@@ -0,0 +1,229 @@
+/// compact.rs implements the Base45 (RFC 9285) and Base62 encodings used for QR codes, URL shorteners and KSUIDs
+
+#[derive(Debug, Clone)]
+pub enum Variant {
+    Standard, //Bytes treated as a big endian number, with each leading zero byte written as a '0'
+    Ksuid, //Fixed width, 20 bytes written as exactly 27 characters
+}
+
+const BASE45_ALPHABET: & [u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+const BASE62_ALPHABET: & [u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+const KSUID_BYTES: usize = 20;
+const KSUID_CHARACTERS: usize = 27;
+
+pub struct Base45Encoding;
+
+impl Base45Encoding {
+    ///Each group of 3 characters is 2 bytes and a final group of 2 characters is 1 byte, so the length can never be 1 more than a multiple of 3
+    pub fn decode(string: &str) -> Result<Vec<u8>, ()> {
+        let digits = string.bytes()
+            .map(|character| BASE45_ALPHABET.iter().position(|&x| x == character).map(|x| x as u32))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(())?;
+
+        let mut bytes = Vec::new();
+
+        for group in digits.chunks(3) {
+            let value = group.iter().rev().fold(0, |acc, &digit| acc * 45 + digit);
+
+            match group.len() {
+                3 if value <= 0xFFFF => bytes.extend_from_slice(&(value as u16).to_be_bytes()),
+                2 if value <= 0xFF => bytes.push(value as u8),
+                _ => return Err(()),
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    ///Any uppercase word decodes, so decoded bytes only count as Base45 when they are text or a zlib stream, as in QR code payloads
+    pub fn is_plausible(bytes: & [u8]) -> bool {
+        let is_zlib = bytes.len() > 2 && bytes[0] & 0x0F == 8 && u16::from_be_bytes([bytes[0], bytes[1]]).is_multiple_of(31);
+
+        is_zlib || std::str::from_utf8(bytes).is_ok_and(|text| !text.is_empty() && crate::unicode::is_text(text))
+    }
+
+    pub fn encode(bytes: & [u8]) -> String {
+        let mut string = String::new();
+
+        for group in bytes.chunks(2) {
+            let (mut value, count) = match group.len() {
+                2 => (u16::from_be_bytes([group[0], group[1]]) as usize, 3),
+                _ => (group[0] as usize, 2),
+            };
+
+            //Base45 writes the least significant digit first
+            for _ in 0..count {
+                string.push(BASE45_ALPHABET[value % 45] as char);
+                value /= 45;
+            }
+        }
+
+        string
+    }
+}
+
+pub struct Base62Encoding;
+
+impl Base62Encoding {
+    pub fn decode(string: &str, variant: Variant) -> Result<Vec<u8>, ()> {
+        let digits = string.bytes()
+            .map(|character| BASE62_ALPHABET.iter().position(|&x| x == character).map(|x| x as u8))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(())?;
+
+        match variant {
+            Variant::Standard => {
+                let zeros = digits.iter().take_while(|&&x| x == 0).count();
+
+                let mut bytes = vec![0; zeros];
+                bytes.extend_from_slice(&Self::convert(&digits[zeros..], 62, 256));
+
+                Ok(bytes)
+            },
+            Variant::Ksuid => {
+                if digits.len() != KSUID_CHARACTERS {
+                    return Err(());
+                }
+
+                let bytes = Self::convert(&digits, 62, 256);
+
+                //27 characters can hold slightly more than 160 bits, so check that the value fits
+                if bytes.len() > KSUID_BYTES {
+                    return Err(());
+                }
+
+                let mut padded = vec![0; KSUID_BYTES - bytes.len()];
+                padded.extend_from_slice(&bytes);
+
+                Ok(padded)
+            },
+        }
+    }
+
+    ///Any alphanumeric word decodes, so an arbitrary length string only counts as Base62 when it mixes digits with upper and lowercase
+    ///letters like generated identifiers do, or when it decodes to text
+    pub fn is_plausible(string: &str, bytes: & [u8]) -> bool {
+        let mixed = string.bytes().any(|x| x.is_ascii_digit()) && string.bytes().any(|x| x.is_ascii_uppercase()) && string.bytes().any(|x| x.is_ascii_lowercase());
+
+        mixed || std::str::from_utf8(bytes).is_ok_and(|text| !text.is_empty() && crate::unicode::is_text(text))
+    }
+
+    ///Encode the bytes. NOTE: KSUID requires exactly 20 bytes
+    pub fn encode(bytes: & [u8], variant: Variant) -> String {
+        let mut digits = match variant {
+            Variant::Standard => {
+                let zeros = bytes.iter().take_while(|&&x| x == 0).count();
+
+                let mut digits = vec![0; zeros];
+                digits.extend_from_slice(&Self::convert(&bytes[zeros..], 256, 62));
+                digits
+            },
+            Variant::Ksuid => Self::convert(bytes, 256, 62),
+        };
+
+        if let Variant::Ksuid = variant {
+            while digits.len() < KSUID_CHARACTERS {
+                digits.insert(0, 0);
+            }
+        }
+
+        digits.iter().map(|&x| BASE62_ALPHABET[x as usize] as char).collect()
+    }
+
+    ///Convert big endian digits from one base to another, without leading zeros
+    fn convert(digits: & [u8], from: u32, to: u32) -> Vec<u8> {
+        let mut result: Vec<u8> = Vec::new();
+
+        for &digit in digits {
+            let mut carry = digit as u32;
+
+            for output in result.iter_mut().rev() {
+                carry += *output as u32 * from;
+                *output = (carry % to) as u8;
+                carry /= to;
+            }
+
+            while carry > 0 {
+                result.insert(0, (carry % to) as u8);
+                carry /= to;
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base45_known_vectors() {
+        //Examples from RFC 9285
+        assert_eq!(Base45Encoding::encode(b"AB"), "BB8");
+        assert_eq!(Base45Encoding::encode(b"Hello!!"), "%69 VD92EX0");
+        assert_eq!(Base45Encoding::encode(b"base-45"), "UJCLQE7W581");
+        assert_eq!(Base45Encoding::decode("QED8WEX0"), Ok(b"ietf!".to_vec()));
+    }
+
+    #[test]
+    fn base45_rejects_invalid_input() {
+        //A length 1 more than a multiple of 3, a group larger than 2 bytes and a character outside the alphabet
+        assert!(Base45Encoding::decode("BB8B").is_err());
+        assert!(Base45Encoding::decode("GGW").is_err());
+        assert!(Base45Encoding::decode("bb8").is_err());
+    }
+
+    #[test]
+    fn base45_plausible() {
+        assert!(Base45Encoding::is_plausible(b"Hello!!"));
+        assert!(Base45Encoding::is_plausible(&[0x78, 0xDA, 0xCB]));
+        assert!(!Base45Encoding::is_plausible(&Base45Encoding::decode("ABCDEF").unwrap()));
+    }
+
+    #[test]
+    fn base62_known_vectors() {
+        assert_eq!(Base62Encoding::encode(b"Hello", Variant::Standard), "5TP3P3v");
+        assert_eq!(Base62Encoding::encode(&[0, 0, 1], Variant::Standard), "001");
+        assert_eq!(Base62Encoding::decode("001", Variant::Standard), Ok(vec![0, 0, 1]));
+    }
+
+    #[test]
+    fn ksuid_known_vector() {
+        //Example KSUID from the segmentio/ksuid readme, a 4 byte timestamp followed by a 16 byte payload
+        let bytes = Base62Encoding::decode("0ujtsYcgvSTl8PAuAdqWYSMnLOv", Variant::Ksuid).unwrap();
+
+        assert_eq!(bytes.len(), KSUID_BYTES);
+        assert_eq!(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]), 107608047);
+        assert_eq!(bytes[4..], [0xB5, 0xA1, 0xCD, 0x34, 0xB5, 0xF9, 0x9D, 0x11, 0x54, 0xFB, 0x68, 0x53, 0x34, 0x5C, 0x97, 0x35]);
+        assert_eq!(Base62Encoding::encode(&bytes, Variant::Ksuid), "0ujtsYcgvSTl8PAuAdqWYSMnLOv");
+
+        //The largest KSUID, and one character too many
+        assert_eq!(Base62Encoding::decode("aWgEPTl1tmebfsQzFP4bxwgy80V", Variant::Ksuid), Ok(vec![0xFF; KSUID_BYTES]));
+        assert!(Base62Encoding::decode("aWgEPTl1tmebfsQzFP4bxwgy80W", Variant::Ksuid).is_err());
+        assert!(Base62Encoding::decode("0ujtsYcgvSTl8PAuAdqWYSMnLO", Variant::Ksuid).is_err());
+    }
+
+    #[test]
+    fn base62_plausible() {
+        assert!(Base62Encoding::is_plausible("aZ3kP9x", &[]));
+        assert!(!Base62Encoding::is_plausible("Hello", &Base62Encoding::decode("Hello", Variant::Standard).unwrap()));
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = [0, 0].iter().copied().chain(0..=255).collect();
+
+        for length in 0..bytes.len() {
+            assert_eq!(Base45Encoding::decode(&Base45Encoding::encode(&bytes[..length])), Ok(bytes[..length].to_vec()));
+            assert_eq!(Base62Encoding::decode(&Base62Encoding::encode(&bytes[..length], Variant::Standard), Variant::Standard), Ok(bytes[..length].to_vec()));
+        }
+
+        for ksuid in bytes.chunks(KSUID_BYTES).filter(|chunk| chunk.len() == KSUID_BYTES) {
+            assert_eq!(Base62Encoding::decode(&Base62Encoding::encode(ksuid, Variant::Ksuid), Variant::Ksuid), Ok(ksuid.to_vec()));
+        }
+    }
+}
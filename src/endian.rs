@@ -5,6 +5,22 @@
 pub enum Endianness {
     Default,
     Dual,
+    DualUnits(usize), //Like Dual, but each unit of n bytes is swapped separately, such as UTF-16 code units. Outputs give each byte order as a variant, so only inputs ask for the byte order
+}
+
+impl Endianness {
+    ///Returns true if the bytes can be interpreted as either little or big endian
+    pub fn is_dual(&self) -> bool {
+        !matches!(self, Endianness::Default)
+    }
+
+    ///Swap the byte order of the whole IR, or of each unit
+    pub fn swap(&self, ir: & [u8]) -> Vec<u8> {
+        match self {
+            Endianness::DualUnits(size) => ir.chunks(*size).flat_map(|unit| unit.iter().rev()).copied().collect(),
+            _ => ir.iter().rev().copied().collect(),
+        }
+    }
 }
 
 
@@ -18,6 +34,12 @@ impl Endian for crate::UnicodeNames {
     }
 }
 
+impl Endian for crate::common::CodePoints {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
 impl Endian for crate::common::Base2_16 {
     fn endianness() -> Endianness {
         Endianness::Default
@@ -48,6 +70,30 @@ impl Endian for crate::common::Unicode8 {
     }
 }
 
+impl Endian for crate::common::Normalization {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::Unicode16 {
+    fn endianness() -> Endianness {
+        Endianness::DualUnits(2)
+    }
+}
+
+impl Endian for crate::common::Unicode32 {
+    fn endianness() -> Endianness {
+        Endianness::DualUnits(4)
+    }
+}
+
+impl Endian for crate::common::Codepage {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
 impl Endian for crate::common::IpV4 {
     fn endianness() -> Endianness {
         Endianness::Default
@@ -72,12 +118,54 @@ impl Endian for crate::common::Base85 {
     }
 }
 
+impl Endian for crate::common::Base58 {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::Base45 {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::Base62 {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
 impl Endian for crate::common::Base64 {
     fn endianness() -> Endianness {
         Endianness::Default
     }
 }
 
+impl Endian for crate::common::Attachment {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::Mime {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::Pem {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::DataUri {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
 impl Endian for crate::common::ByteList {
     fn endianness() -> Endianness {
         Endianness::Default
@@ -96,6 +184,54 @@ impl Endian for crate::common::EscapedString {
     }
 }
 
+impl Endian for crate::common::StringLiteral {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::HtmlEntities {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::RegexLiteral {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::SqlString {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::ShellWord {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::UrlComponents {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::Punycode {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
+impl Endian for crate::common::Idna {
+    fn endianness() -> Endianness {
+        Endianness::Default
+    }
+}
+
 impl Endian for crate::common::UrlEncode {
     fn endianness() -> Endianness {
         Endianness::Default
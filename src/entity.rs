@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use lazy_static::lazy_static;
+use entities::{ENTITIES, Codepoints};
+
+/// entity.rs implements HTML5 character references, both named and numeric, and the stricter XML form with only 5 predefined entities
+
+#[derive(Debug, Clone)]
+pub enum Variant {
+    Html, //Any HTML5 named entity, including the legacy ones without a semicolon, and numeric references
+    Xml, //Only &amp; &lt; &gt; &quot; &apos; and numeric references, with every '&' starting a reference
+}
+
+#[derive(Debug, Clone)]
+pub enum Escape {
+    Minimal, //Only the characters with a meaning in markup
+    Named, //Also every non-ASCII character, using a named entity where there is one
+    Decimal, //Also every non-ASCII character, as a decimal reference
+    Hex, //Also every non-ASCII character, as a hex reference
+    Xml, //Only the characters with a meaning in markup, using the XML entities
+}
+
+const XML_ENTITIES: [(&str, char); 5] = [("amp", '&'), ("lt", '<'), ("gt", '>'), ("quot", '"'), ("apos", '\'')];
+
+lazy_static! {
+    //Characters of each entity, keyed by the entity without the '&'
+    static ref BY_NAME: HashMap<& 'static str, & 'static str> = ENTITIES.iter().map(|entity| (&entity.entity[1..], entity.characters)).collect();
+
+    //Shortest named entity for each character, preferring lowercase ones such as '&copy;' over '&COPY;'. Only entities that end with a
+    //semicolon are used
+    static ref BY_CHARACTER: HashMap<char, & 'static str> = {
+        let mut map: HashMap<char, & 'static str> = HashMap::new();
+
+        let rank = |entity: &str| (entity.len(), entity.bytes().filter(|x| x.is_ascii_uppercase()).count());
+
+        for entity in ENTITIES.iter().filter(|entity| entity.entity.ends_with(';')) {
+            if let Codepoints::Single(code_point) = entity.codepoints {
+                let character = char::from_u32(code_point).unwrap();
+
+                match map.get(&character) {
+                    Some(existing) if rank(existing) <= rank(entity.entity) => (),
+                    _ => {
+                        map.insert(character, entity.entity);
+                    },
+                }
+            }
+        }
+
+        map
+    };
+}
+
+pub struct EntityEncoding;
+
+impl EntityEncoding {
+    pub fn decode(string: &str, variant: Variant) -> Result<String, ()> {
+        let strict = matches!(variant, Variant::Xml);
+
+        let mut decoded = String::new();
+        let mut rest = string;
+
+        while let Some(index) = rest.find('&') {
+            decoded.push_str(&rest[..index]);
+            rest = &rest[index + 1..];
+
+            match Self::decode_reference(rest, strict)? {
+                Some((characters, length)) => {
+                    decoded.push_str(&characters);
+                    rest = &rest[length..];
+                },
+                //HTML allows an '&' that does not start a reference
+                None => decoded.push('&'),
+            }
+        }
+
+        decoded.push_str(rest);
+
+        Ok(decoded)
+    }
+
+    ///Decode the reference following an '&', returning the characters and the length of the reference. In XML, numeric references that are
+    ///not XML characters are an error, and so is anything that is not a reference
+    fn decode_reference(string: &str, strict: bool) -> Result<Option<(String, usize)>, ()> {
+        if let Some(numeric) = string.strip_prefix('#') {
+            let (digits, radix, prefix) = match numeric.strip_prefix(['x', 'X']) {
+                Some(hex) => (hex, 16, 2),
+                None => (numeric, 10, 1),
+            };
+
+            let length = digits.find(|x: char| !x.is_digit(radix)).unwrap_or(digits.len());
+
+            if length == 0 || !digits[length..].starts_with(';') {
+                return if strict { Err(()) } else { Ok(None) };
+            }
+
+            let character = match u32::from_str_radix(&digits[..length], radix).ok().and_then(char::from_u32) {
+                Some(character) if strict && Self::is_xml_character(character) => character,
+                _ if strict => return Err(()),
+                //HTML replaces NUL, surrogates and code points past U+10FFFF, and reads 0x80 to 0x9F as Windows-1252
+                Some('\0') | None => char::REPLACEMENT_CHARACTER,
+                Some(character @ '\u{80}'..='\u{9F}') => {
+                    let byte = [character as u8];
+                    let (decoded, _) = encoding_rs::WINDOWS_1252.decode_without_bom_handling(&byte);
+                    decoded.chars().next().unwrap()
+                },
+                Some(character) => character,
+            };
+
+            return Ok(Some((character.to_string(), prefix + length + 1)));
+        }
+
+        let length = string.find(|x: char| !x.is_ascii_alphanumeric()).unwrap_or(string.len());
+        let name = &string[..length];
+
+        if strict {
+            return match XML_ENTITIES.iter().find(|(entity, _)| *entity == name) {
+                Some((_, character)) if string[length..].starts_with(';') => Ok(Some((character.to_string(), length + 1))),
+                _ => Err(()),
+            };
+        }
+
+        if string[length..].starts_with(';') {
+            if let Some(characters) = BY_NAME.get(&string[..length + 1]) {
+                return Ok(Some((characters.to_string(), length + 1)));
+            }
+        }
+
+        //Legacy entities such as '&amp' can be written without a semicolon, in which case the longest one that matches is used
+        for end in (1..=length).rev() {
+            if let Some(characters) = BY_NAME.get(&name[..end]) {
+                return Ok(Some((characters.to_string(), end)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn encode(string: &str, escape: Escape) -> String {
+        string.chars().map(|character| match (character, &escape) {
+            ('&', _) => String::from("&amp;"),
+            ('<', _) => String::from("&lt;"),
+            ('>', _) => String::from("&gt;"),
+            ('"', _) => String::from("&quot;"),
+            //&apos; is not in HTML 4, so a numeric reference is used for HTML
+            ('\'', Escape::Xml) => String::from("&apos;"),
+            ('\'', _) => String::from("&#39;"),
+            (_, Escape::Minimal | Escape::Xml) => character.to_string(),
+            _ if character.is_ascii() => character.to_string(),
+            (_, Escape::Named) => match BY_CHARACTER.get(&character) {
+                Some(entity) => entity.to_string(),
+                None => format!("&#x{:X};", character as u32),
+            },
+            (_, Escape::Decimal) => format!("&#{};", character as u32),
+            (_, Escape::Hex) => format!("&#x{:X};", character as u32),
+        }).collect()
+    }
+
+    ///Returns true if the character can be written in XML 1.0, either directly or as a numeric reference
+    pub fn is_xml_character(character: char) -> bool {
+        matches!(character, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn html(string: &str) -> Result<String, ()> {
+        EntityEncoding::decode(string, Variant::Html)
+    }
+
+    fn xml(string: &str) -> Result<String, ()> {
+        EntityEncoding::decode(string, Variant::Xml)
+    }
+
+    #[test]
+    fn named_references() {
+        assert_eq!(html("&lt;p&gt; &copy; &eacute;t&eacute;"), Ok(String::from("<p> \u{A9} \u{E9}t\u{E9}")));
+        assert_eq!(html("&NotNestedGreaterGreater;"), Ok(String::from("\u{2AA2}\u{338}")));
+
+        //Legacy entities without a semicolon use the longest match, and unknown entities are left alone
+        assert_eq!(html("&copy2021 &notit;"), Ok(String::from("\u{A9}2021 \u{AC}it;")));
+        assert_eq!(html("AT&T &unknown;"), Ok(String::from("AT&T &unknown;")));
+    }
+
+    #[test]
+    fn numeric_references() {
+        assert_eq!(html("&#65;&#x42;&#X43;"), Ok(String::from("ABC")));
+        assert_eq!(html("&#x1F600;"), Ok(String::from("\u{1F600}")));
+
+        //NUL, surrogates and code points past U+10FFFF are replaced, and 0x80 to 0x9F are read as Windows-1252
+        assert_eq!(html("&#0;&#xD800;&#x110000;&#99999999999;"), Ok("\u{FFFD}".repeat(4)));
+        assert_eq!(html("&#128;&#x99;&#x81;"), Ok(String::from("\u{20AC}\u{2122}\u{81}")));
+
+        assert_eq!(html("&#65 &#;"), Ok(String::from("&#65 &#;")));
+    }
+
+    #[test]
+    fn xml_references() {
+        assert_eq!(xml("&lt;a href=&quot;x&quot;&gt; &apos;&amp;&apos; &#233;"), Ok(String::from("<a href=\"x\"> '&' \u{E9}")));
+        assert_eq!(xml("&#128;"), Ok(String::from("\u{80}")));
+
+        //Only the 5 predefined entities, and every '&' starts a reference
+        assert!(xml("&copy;").is_err());
+        assert!(xml("AT&T").is_err());
+        assert!(xml("&amp").is_err());
+        assert!(xml("&#0;").is_err());
+        assert!(xml("&#xFFFE;").is_err());
+    }
+
+    #[test]
+    fn encode_escapes() {
+        let text = "<\"caf\u{E9}\" & 'th\u{E9}'> \u{1F600}";
+
+        assert_eq!(EntityEncoding::encode(text, Escape::Minimal), "&lt;&quot;caf\u{E9}&quot; &amp; &#39;th\u{E9}&#39;&gt; \u{1F600}");
+        assert_eq!(EntityEncoding::encode(text, Escape::Named), "&lt;&quot;caf&eacute;&quot; &amp; &#39;th&eacute;&#39;&gt; &#x1F600;");
+        assert_eq!(EntityEncoding::encode(text, Escape::Decimal), "&lt;&quot;caf&#233;&quot; &amp; &#39;th&#233;&#39;&gt; &#128512;");
+        assert_eq!(EntityEncoding::encode(text, Escape::Hex), "&lt;&quot;caf&#xE9;&quot; &amp; &#39;th&#xE9;&#39;&gt; &#x1F600;");
+        assert_eq!(EntityEncoding::encode(text, Escape::Xml), "&lt;&quot;caf\u{E9}&quot; &amp; &apos;th\u{E9}&apos;&gt; \u{1F600}");
+
+        //The lowercase entity is preferred
+        assert_eq!(EntityEncoding::encode("\u{A9}", Escape::Named), "&copy;");
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "<tag attr=\"a & b\">'\u{A0}\u{E9}\u{2014}\u{1F600}'</tag>";
+
+        for escape in [Escape::Minimal, Escape::Named, Escape::Decimal, Escape::Hex] {
+            assert_eq!(html(&EntityEncoding::encode(text, escape.clone())), Ok(String::from(text)), "{:?}", escape);
+        }
+
+        assert_eq!(xml(&EntityEncoding::encode(text, Escape::Xml)), Ok(String::from(text)));
+    }
+}
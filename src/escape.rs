@@ -2,7 +2,6 @@ use std::str::{from_utf8, from_utf8_unchecked};
 use crate::toir::ToIR;
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::convert::TryInto;
 
 /// escape.rs defines a set of tools for interpreting and creating different types of escape sequences
 
@@ -24,59 +23,112 @@ enum Escapes {
     CarriageReturn,
     HorizontalTab,
     VerticalTab,
+    Escape,
 }
 
 #[derive(Debug, Clone)]
 pub enum Variant {
     C,
     Python,
+    Rust, //\u{..} and \x up to 7F, no octal
+    Json, //Only \" \\ \/ \b \f \n \r \t and \uXXXX, with surrogate pairs
+    JavaScript, //\xHH, \uXXXX with surrogate pairs and \u{..}
+    Java, //Octal up to \377 and \uXXXX with surrogate pairs
+    CSharp, //\x with 1 to 4 hex digits for a UTF-16 code unit, \uXXXX with surrogate pairs and \UXXXXXXXX, no octal
+    Go, //\xHH, 3 digit octal, \uXXXX and \UXXXXXXXX, or a raw string in back quotes
+    Bash, //ANSI-C quoting, $'..'
+    PowerShell, //Backtick escapes in double quoted strings
 }
 
-impl<'t> From<& 't [u8]> for Escapes {
-    fn from(string: &[u8]) -> Self {
-
-        if string == b"\\\\" {
-            Self::Backslash
-        } else if string == b"\\\"" {
-            Self::DoubleQuote
-        } else if string == b"\\\'"  {
-            Self::SingleQuote
-        } else if string == b"\\a" {
-            Self::Bell
-        } else if  string == b"\\b" {
-            Self::Backspace
-        } else if  string == b"\\f" {
-            Self::FormFeed
-        } else if  string == b"\\n" {
-            Self::LineFeed
-        } else if  string == b"\\r" {
-            Self::CarriageReturn
-        } else if  string == b"\\t" {
-            Self::HorizontalTab
-        } else if  string == b"\\v" {
-            Self::VerticalTab
-        } else if string.len() > 1  {
-            if &string[0..2] == b"\\x" {
-
-                let bytes = crate::Base2_16::decode(from_utf8(&string[2..]).unwrap(), crate::Variant("Base 16"));
-
-                Self::Bytes(bytes)
-            } else if &string[0..1] == b"\\" && string[1] >= '0' as u8 && string[1] <= '7' as u8 {
-                let bytes = crate::Base2_16::decode(from_utf8(&string[1..]).unwrap(), crate::Variant("Base 8"));
-
-                Self::Byte(bytes[0])
-
-            } else {
-                panic!("Invalid escape sequence")
-            }
-        } else {
-            let byte = string[0];
-            Self::Literal(byte)
+impl Variant {
+    pub const ALL: [Variant; 10] = [Variant::C, Variant::Python, Variant::Rust, Variant::Json, Variant::JavaScript, Variant::Java, Variant::CSharp, Variant::Go, Variant::Bash, Variant::PowerShell];
+
+    pub fn name(&self) -> & 'static str {
+        match self {
+            Variant::C => "C",
+            Variant::Python => "Python",
+            Variant::Rust => "Rust",
+            Variant::Json => "JSON",
+            Variant::JavaScript => "JavaScript",
+            Variant::Java => "Java",
+            Variant::CSharp => "C#",
+            Variant::Go => "Go",
+            Variant::Bash => "Bash",
+            Variant::PowerShell => "PowerShell",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|variant| variant.name() == name).cloned()
+    }
+
+    ///Returns true if the string uses the escapes or quoting of the dialect. C and Python accept any string
+    pub fn is_used_in(&self, string: &str) -> bool {
+        match self {
+            Variant::C | Variant::Python => true,
+            Variant::Go => string.contains('\\') || Self::is_raw(string),
+            Variant::Bash => string.len() >= 3 && string.starts_with("$'") && string.ends_with('\''),
+            Variant::PowerShell => string.contains('`'),
+            _ => string.contains('\\'),
+        }
+    }
+
+    //Go raw strings are written in back quotes
+    fn is_raw(string: &str) -> bool {
+        string.len() >= 2 && string.starts_with('`') && string.ends_with('`')
+    }
+
+    ///Opening quotes of the dialect's string literals. The closing quote is the last character
+    pub fn quotes(&self) -> & 'static [& 'static str] {
+        match self {
+            Variant::Python => &["'", "\"", "b'", "b\""],
+            Variant::Rust => &["\"", "b\""],
+            Variant::JavaScript => &["'", "\""],
+            Variant::Go => &["\"", "`"],
+            Variant::Bash => &["$'"],
+            _ => &["\""],
+        }
+    }
+
+    ///The dialect with the quotes of a literal, such as Python b'..'
+    pub fn label(&self, quote: &str) -> String {
+        format!("{} {}..{}", self.name(), quote, quote.chars().last().unwrap())
+    }
+
+    fn escape_character(&self) -> u8 {
+        match self {
+            Variant::PowerShell => b'`',
+            _ => b'\\',
         }
     }
 }
 
 impl Escapes {
+    ///Escapes made of a single character after the escape character
+    fn simple(variant: &Variant, character: u8) -> Option<Self> {
+        use Variant::*;
+
+        match (character, variant) {
+            (b'\\', PowerShell) => None,
+            (b'\\', _) => Some(Self::Backslash),
+            (b'"', _) => Some(Self::DoubleQuote),
+            (b'\'', Json) => None,
+            (b'\'', _) => Some(Self::SingleQuote),
+            (b'a', C | Python | CSharp | Go | Bash | PowerShell) => Some(Self::Bell),
+            (b'b', C | Python | Json | JavaScript | Java | CSharp | Go | Bash | PowerShell) => Some(Self::Backspace),
+            (b'f', C | Python | Json | JavaScript | Java | CSharp | Go | Bash | PowerShell) => Some(Self::FormFeed),
+            (b'n', _) => Some(Self::LineFeed),
+            (b'r', _) => Some(Self::CarriageReturn),
+            (b't', _) => Some(Self::HorizontalTab),
+            (b'v', C | Python | JavaScript | CSharp | Go | Bash | PowerShell) => Some(Self::VerticalTab),
+            (b'e' | b'E', Bash) | (b'e', CSharp | PowerShell) => Some(Self::Escape),
+            (b'0', Rust | CSharp | PowerShell) => Some(Self::Byte(0)),
+            (b'/', Json) | (b'?', C | Bash) | (b'`' | b'$', PowerShell) => Some(Self::Literal(character)),
+            (b's', Java) => Some(Self::Literal(b' ')),
+            _ => None,
+        }
+    }
+
     fn append_bytes(& self, vector: & mut Vec<u8>) {
         match &self {
             Escapes::Literal(byte) => {vector.push(*byte)}
@@ -91,6 +143,7 @@ impl Escapes {
             Escapes::CarriageReturn => {vector.push('\r' as u8)}
             Escapes::HorizontalTab => {vector.push('\t' as u8)}
             Escapes::VerticalTab => {vector.push(11)}
+            Escapes::Escape => {vector.push(27)}
             Escapes::Byte(byte) => {vector.push(*byte)}
             Escapes::Unicode(character) => {
                 vector.extend_from_slice(character.to_string().as_bytes())
@@ -99,274 +152,590 @@ impl Escapes {
     }
 }
 
+//What a literal holds, which decides what its numeric escapes mean
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Text,
+    Bytes, //Byte string literals are ASCII, \x goes up to FF and there are no Unicode escapes
+    Wide, //C wide and Unicode literals, where \x and octal escapes are code points rather than bytes
+}
+
+//A character, or a byte that is not part of one
+enum Unit {
+    Character(char),
+    Byte(u8),
+}
+
 struct EscapedStringIterator<'t> {
     remaining: & 't [u8],
     variant: Variant,
+    kind: Kind,
 }
 
 impl<'t> EscapedStringIterator<'t> {
-    pub fn new(string: & 't str, variant: Variant) -> Self {
+    pub fn new(string: & 't str, variant: Variant, kind: Kind) -> Self {
         Self {
             remaining: string.as_bytes(),
-            variant
+            variant,
+            kind,
+        }
+    }
+
+    //Stops the iteration with an error
+    fn invalid(&mut self) -> Option<Result<Escapes, ()>> {
+        self.remaining = &self.remaining[0..0];
+        Some(Err(()))
+    }
+
+    fn advance(&mut self, length: usize, escape: Escapes) -> Option<Result<Escapes, ()>> {
+        self.remaining = &self.remaining[length..];
+        Some(Ok(escape))
+    }
+
+    //Number of hex or octal digits starting at index, up to the maximum
+    fn digits(&self, start: usize, maximum: usize, radix: u32) -> usize {
+        let is_digit = if radix == 16 { crate::Base2_16::is_hex } else { crate::Base2_16::is_oct };
+
+        self.remaining.iter().skip(start).take(maximum).take_while(|&&x| is_digit(x)).count()
+    }
+
+    fn value(&self, start: usize, count: usize, radix: u32) -> u32 {
+        self.remaining[start..start + count].iter().fold(0, |value, &x| value * radix + crate::common::Base2_16::ascii_to_num(x) as u32)
+    }
+
+    ///\x followed by between minimum and 2 hex digits, giving a byte no larger than the limit
+    fn hex_byte(&mut self, minimum: usize, limit: u32) -> Option<Result<Escapes, ()>> {
+        let count = self.digits(2, 2, 16);
+        let value = self.value(2, count, 16);
+
+        if count < minimum || value > limit {
+            return self.invalid();
+        }
+
+        self.advance(count + 2, Escapes::Byte(value as u8))
+    }
+
+    ///A code point written with between minimum and maximum hex digits
+    fn code_point(&mut self, minimum: usize, maximum: usize) -> Option<Result<Escapes, ()>> {
+        let count = self.digits(2, maximum, 16);
+
+        match char::from_u32(self.value(2, count, 16)) {
+            Some(character) if count >= minimum => self.advance(count + 2, Escapes::Unicode(character)),
+            _ => self.invalid(),
         }
     }
+
+    ///A code point written with 1 to 6 hex digits in braces, such as \u{1F600}
+    fn braced_code_point(&mut self) -> Option<Result<Escapes, ()>> {
+        let count = self.digits(3, 6, 16);
+
+        if self.remaining.get(2) != Some(&b'{') || count == 0 || self.remaining.get(count + 3) != Some(&b'}') {
+            return self.invalid();
+        }
+
+        match char::from_u32(self.value(3, count, 16)) {
+            Some(character) => self.advance(count + 4, Escapes::Unicode(character)),
+            None => self.invalid(),
+        }
+    }
+
+    //A \uXXXX code unit at the index, returning the value and the index after it. Java allows any number of 'u's
+    fn code_unit(&self, start: usize) -> Option<(u32, usize)> {
+        if self.remaining.get(start) != Some(&b'\\') || self.remaining.get(start + 1) != Some(&b'u') {
+            return None;
+        }
+
+        let mut index = start + 2;
+
+        if matches!(self.variant, Variant::Java) {
+            while self.remaining.get(index) == Some(&b'u') {
+                index += 1;
+            }
+        }
+
+        if self.digits(index, 4, 16) != 4 {
+            return None;
+        }
+
+        Some((self.value(index, 4, 16), index + 4))
+    }
+
+    ///A UTF-16 code unit, combined with the next one if they form a surrogate pair. Lone surrogates are an error
+    fn utf16_code_point(&mut self) -> Option<Result<Escapes, ()>> {
+        let (code_point, end) = match self.code_unit(0) {
+            Some((high @ 0xD800..=0xDBFF, end)) => match self.code_unit(end) {
+                Some((low @ 0xDC00..=0xDFFF, end)) => (0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00), end),
+                _ => return self.invalid(),
+            },
+            Some(unit) => unit,
+            None => return self.invalid(),
+        };
+
+        match char::from_u32(code_point) {
+            Some(character) => self.advance(end, Escapes::Unicode(character)),
+            None => self.invalid(),
+        }
+    }
+
+    ///Octal escape with between minimum and maximum digits, no larger than 0o377
+    fn octal_byte(&mut self, minimum: usize, maximum: usize) -> Option<Result<Escapes, ()>> {
+        let count = self.digits(1, maximum, 8);
+        let value = self.value(1, count, 8);
+
+        if count < minimum || value > 0o377 {
+            return self.invalid();
+        }
+
+        self.advance(count + 1, Escapes::Byte(value as u8))
+    }
 }
 
 impl<'t> Iterator for EscapedStringIterator<'t> {
     type Item = Result<Escapes, ()>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        use Variant::*;
 
         if self.remaining.is_empty() {
             return None;
         }
 
-        let slice = if self.remaining[0] == '\\' as u8 {
-            let ch = self.remaining[1] as char;
-            if ch == '\\' || ch == '\'' || ch == '\"' || ch == 'a' || ch == 'b' || ch == 'f' || ch == 'n' || ch == 'r' || ch == 't' || ch == 'v' {
-                let slice = &self.remaining[0..2];
-                self.remaining = &self.remaining[2..];
-                slice
-            } else if ch == 'x' {
+        if self.remaining[0] != self.variant.escape_character() {
+            let byte = self.remaining[0];
 
-                let mut hex_character_count = 0;
+            //JSON strings cannot contain unescaped quotes or control characters, and $'..' ends at an unescaped quote
+            return match (byte, &self.variant) {
+                (b'"' | 0..=0x1F, Json) | (b'\'', Bash) => self.invalid(),
+                _ if self.kind == Kind::Bytes && !byte.is_ascii() => self.invalid(),
+                _ => self.advance(1, Escapes::Literal(byte)),
+            };
+        }
 
-                for byte in &self.remaining[2..] {
-                    if crate::Base2_16::is_hex(*byte) {
-                        hex_character_count += 1;
-                    } else {
-                        break;
-                    }
-                }
+        let ch = match self.remaining.get(1) {
+            Some(&ch) => ch,
+            //A trailing escape character does not escape anything
+            None => return self.invalid(),
+        };
 
-                let advanceby = match self.variant {
-                    Variant::C => {
-                        if hex_character_count == 0 {
-                            self.remaining = &self.remaining[0..0];
-                            return Some(Err(()))
-                        }
-
-                        hex_character_count + 2
-                    }
-                    Variant::Python => {
-                        if hex_character_count < 2 {
-                            self.remaining = &self.remaining[0..0];
-                            return Some(Err(()))
-                        }
-
-                        4
-                    }
-                };
+        if self.kind == Kind::Bytes && matches!(ch, b'u' | b'U' | b'N') {
+            return self.invalid();
+        }
+
+        if let Some(escape) = Escapes::simple(&self.variant, ch) {
+            return self.advance(2, escape);
+        }
+
+        match (ch, &self.variant) {
+            //Line continuations, Rust also skips the leading whitespace of the next line
+            (b'\n', Rust) => {
+                let whitespace = self.remaining[2..].iter().take_while(|x| x.is_ascii_whitespace()).count();
+                self.remaining = &self.remaining[whitespace + 2..];
+                self.next()
+            }
+            (b'\n', C | Python | JavaScript) => {
+                self.remaining = &self.remaining[2..];
+                self.next()
+            }
+            //Wide literals have a code point for every hex digit after the \x, up to the largest code point
+            (b'x', C) if self.kind == Kind::Wide => match self.digits(2, 9, 16) {
+                9 => self.invalid(),
+                _ => self.code_point(1, 8),
+            },
+            (b'0'..=b'7', C) if self.kind == Kind::Wide => {
+                let count = self.digits(1, 3, 8);
+
+                match char::from_u32(self.value(1, count, 8)) {
+                    Some(character) => self.advance(count + 1, Escapes::Unicode(character)),
+                    None => self.invalid(),
+                }
+            }
+            (b'x', C) => {
+                let hex_character_count = self.digits(2, usize::MAX, 16);
 
-                let slice = &self.remaining[0..advanceby];
-                self.remaining = &self.remaining[advanceby..];
-                slice
-
-            } else if ch == 'N' {
-                match self.variant {
-                    Variant::C => {
-                        //C strings do not support named unicode characters
-                        self.remaining = &self.remaining[0..0];
-                        return Some(Err(()))
-                    }
-                    Variant::Python => {
-                        lazy_static! {
-                            static ref UNICODE_NAME: Regex = Regex::new("\\{([^}]*)\\}").unwrap();
-                        }
-
-
-                        let captures = UNICODE_NAME.captures(unsafe {from_utf8_unchecked(self.remaining)});
-
-
-                        match captures {
-                            Some(groups) => {
-                                //If the capture matches, then a capture group at 1 will always exist
-                                let name = groups.get(1).unwrap();
-
-                                match unicode_names2::character(name.as_str()) {
-                                    None => {
-                                        self.remaining = &self.remaining[0..0];
-                                        return Some(Err(()))
-                                    }
-                                    Some(character) => {
-
-                                        self.remaining = &self.remaining[name.end()+1..];
-                                        return Some(Ok(Escapes::Unicode(character)));
-                                    }
-                                }
-                            },
-                            None => {
-                                self.remaining = &self.remaining[0..0];
-                                return Some(Err(()))
-                            }
-                        }
-
-                    }
+                if hex_character_count == 0 {
+                    return self.invalid();
                 }
-            } else if ch == 'u' {
 
-                let mut hex_character_count = 0;
+                let bytes = crate::Base2_16::decode(from_utf8(&self.remaining[2..hex_character_count + 2]).unwrap(), crate::Variant::new("Base 16"));
 
-                for byte in &self.remaining[2..] {
-                    if crate::Base2_16::is_hex(*byte) {
-                        hex_character_count += 1;
-                    } else {
-                        break;
-                    }
+                self.advance(hex_character_count + 2, Escapes::Bytes(bytes))
+            }
+            (b'x', Python | Go) => self.hex_byte(2, 0xFF),
+            (b'x', Rust) => self.hex_byte(2, if self.kind == Kind::Bytes { 0xFF } else { 0x7F }),
+            (b'x', Bash) => self.hex_byte(1, 0xFF),
+            //JavaScript's \xHH is a code unit rather than a byte
+            (b'x', JavaScript) => self.code_point(2, 2),
+            //C#'s \x takes as many as 4 hex digits, for a UTF-16 code unit
+            (b'x', CSharp) => self.code_point(1, 4),
+            (b'N', Python) => {
+                lazy_static! {
+                    static ref UNICODE_NAME: Regex = Regex::new("^\\\\N\\{([^}]*)\\}").unwrap();
                 }
 
-                if hex_character_count != 4 {
-                    self.remaining = &self.remaining[0..0];
-                    return Some(Err(()))
+                let captures = UNICODE_NAME.captures(unsafe {from_utf8_unchecked(self.remaining)});
+
+                match captures.and_then(|groups| groups.get(1)) {
+                    Some(name) => match unicode_names2::character(name.as_str()) {
+                        Some(character) => self.advance(name.end() + 1, Escapes::Unicode(character)),
+                        None => self.invalid(),
+                    },
+                    None => self.invalid(),
                 }
+            }
+            (b'u', Rust | JavaScript | PowerShell) if self.remaining.get(2) == Some(&b'{') => self.braced_code_point(),
+            (b'u', Json | JavaScript | Java | CSharp) => self.utf16_code_point(),
+            (b'u', C | Python | Go) => self.code_point(4, 4),
+            (b'u', Bash) => self.code_point(1, 4),
+            (b'U', C | Python | CSharp | Go) => self.code_point(8, 8),
+            (b'U', Bash) => self.code_point(1, 8),
+            (b'0'..=b'7', C | Python) => {
+                let octal_character_count = self.digits(1, 3, 8);
+
+                let bytes = crate::Base2_16::decode(from_utf8(&self.remaining[1..octal_character_count + 1]).unwrap(), crate::Variant::new("Base 8"));
+
+                self.advance(octal_character_count + 1, Escapes::Byte(bytes[0]))
+            }
+            //Java octal escapes only have 3 digits if the first is 0 to 3
+            (b'0'..=b'3', Java) => self.octal_byte(1, 3),
+            (b'4'..=b'7', Java) => self.octal_byte(1, 2),
+            (b'0'..=b'7', Go) => self.octal_byte(3, 3),
+            (b'0'..=b'7', Bash) => {
+                let count = self.digits(1, 3, 8);
+                let value = self.value(1, count, 8);
+
+                self.advance(count + 1, Escapes::Byte(value as u8))
+            }
+            //\0 is the null character in JavaScript, as long as it is not followed by a digit
+            (b'0', JavaScript) if !self.remaining.get(2).is_some_and(|x| x.is_ascii_digit()) => self.advance(2, Escapes::Byte(0)),
+            (b'0'..=b'9', JavaScript) => self.invalid(),
+            //Control characters, such as \cA for 0x01
+            (b'c', Bash) => match self.remaining.get(2) {
+                Some(&control) if control.is_ascii_graphic() => self.advance(3, Escapes::Byte(control & 0x1F)),
+                _ => self.invalid(),
+            },
+            //Any other escaped character is the character itself, although letters and digits are more likely another dialect's escape
+            (_, JavaScript | PowerShell) if !ch.is_ascii_alphanumeric() => {
+                let character = unsafe {from_utf8_unchecked(&self.remaining[1..])}.chars().next().unwrap();
+
+                self.advance(character.len_utf8() + 1, Escapes::Unicode(character))
+            }
+            _ => self.invalid(),
+        }
+    }
+}
 
-                let get_byte = |i| {
-                    crate::common::Base2_16::ascii_to_num(self.remaining[i]) as u32
+pub struct EscapeSequence;
+
+impl EscapeSequence {
+    pub fn decode(string: &str, variant: Variant) -> Result<Vec<u8>, ()> {
+        let string = match variant {
+            //Raw strings have no escapes, and carriage returns are discarded
+            Variant::Go if Variant::is_raw(string) => {
+                let raw = &string[1..string.len() - 1];
+
+                return if raw.contains('`') {
+                    Err(())
+                } else {
+                    Ok(raw.replace('\r', "").into_bytes())
                 };
+            },
+            Variant::Bash => string.strip_prefix("$'").and_then(|x| x.strip_suffix('\'')).ok_or(())?,
+            _ => string,
+        };
 
-                let character: u32 = (((((get_byte(2) << 4) | get_byte(3)) << 4) | get_byte(4)) << 4) | get_byte(5);
+        Self::decode_iterator(EscapedStringIterator::new(string, variant, Kind::Text))
+    }
 
-                match character.try_into() {
-                    Ok(ch) => {
-                        self.remaining = &self.remaining[6..];
-                        return Some(Ok(Escapes::Unicode(ch)));
-                    },
-                    Err(_) => {
-                        self.remaining = &self.remaining[0..0];
-                        return Some(Err(()))
-                    }
-                }
+    ///Decode the body of a byte string literal, such as Python's b'..' or Rust's b"..", which is ASCII with escapes for each byte
+    pub fn decode_bytes(string: &str, variant: Variant) -> Result<Vec<u8>, ()> {
+        Self::decode_iterator(EscapedStringIterator::new(string, variant, Kind::Bytes))
+    }
 
+    ///Decode the body of a C wide or Unicode literal, such as L".." or u"..", to UTF-8. Numeric escapes are code points, and the text
+    ///can then be written with code units of the literal's width
+    pub fn decode_wide(string: &str) -> Result<String, ()> {
+        let bytes = Self::decode_iterator(EscapedStringIterator::new(string, Variant::C, Kind::Wide))?;
 
+        String::from_utf8(bytes).map_err(|_| ())
+    }
 
+    fn decode_iterator(iterator: EscapedStringIterator) -> Result<Vec<u8>, ()> {
+        let mut bytes = Vec::new();
 
-            } else if ch == 'U' {
-                let mut hex_character_count = 0;
+        for escaped in iterator {
+            (escaped?).append_bytes(& mut bytes);
+        }
 
-                for byte in &self.remaining[2..] {
-                    if crate::Base2_16::is_hex(*byte) {
-                        hex_character_count += 1;
-                    } else {
-                        break;
-                    }
-                }
+        Ok(bytes)
+    }
+
+    ///Encode the bytes as a string literal in the dialect, including its quotes. The quote is the opening quote of the literal, one
+    ///of the dialect's quotes, such as `'` or `b'` for a Python bytes literal. Returns None if the literal cannot hold the bytes
+    pub fn encode(bytes: & [u8], variant: Variant, quote: &str) -> Option<String> {
+        let closing = quote.chars().last().unwrap();
+
+        //C strings and byte literals are written byte by byte, other literals are text and only Go and Bash can hold other bytes
+        let mut units = Vec::new();
+
+        if matches!(variant, Variant::C) || quote.starts_with('b') {
+            units.extend(bytes.iter().map(|&byte| if byte.is_ascii() { Unit::Character(byte as char) } else { Unit::Byte(byte) }));
+        } else {
+            for chunk in bytes.utf8_chunks() {
+                units.extend(chunk.valid().chars().map(Unit::Character));
 
-                if hex_character_count != 8 {
-                    self.remaining = &self.remaining[0..0];
-                    return Some(Err(()))
+                if !chunk.invalid().is_empty() && !matches!(variant, Variant::Go | Variant::Bash) {
+                    return None;
                 }
 
-                let get_byte = |i| {
-                    crate::common::Base2_16::ascii_to_num(self.remaining[i]) as u32
-                };
+                units.extend(chunk.invalid().iter().map(|&byte| Unit::Byte(byte)));
+            }
+        }
 
-                let character: u32 = (((((get_byte(2) << 4) | get_byte(3)) << 4) | get_byte(4)) << 4) | get_byte(5);
+        //Go raw strings have no escapes, and can't hold carriage returns
+        if quote == "`" {
+            return match from_utf8(bytes) {
+                Ok(text) if !text.chars().any(|x| x == '`' || x.is_control() && x != '\t' && x != '\n') => Some(format!("`{}`", text)),
+                _ => None,
+            };
+        }
 
-                let character: u32 = character << 16 | (((((get_byte(6) << 4) | get_byte(7)) << 4) | get_byte(8)) << 4) | get_byte(9);
+        let mut string = String::from(quote);
 
-                match character.try_into() {
-                    Ok(ch) => {
-                        self.remaining = &self.remaining[10..];
-                        return Some(Ok(Escapes::Unicode(ch)));
-                    },
-                    Err(_) => {
-                        self.remaining = &self.remaining[0..0];
-                        return Some(Err(()))
-                    }
-                }
+        for (index, unit) in units.iter().enumerate() {
+            let previous = index.checked_sub(1).map(|index| &units[index]);
 
+            let next = match units.get(index + 1) {
+                Some(Unit::Character(character)) => Some(*character),
+                _ => None,
+            };
 
+            Self::escape(& mut string, unit, &variant, closing, matches!(previous, Some(Unit::Character('?'))), next);
+        }
 
-            } else if ch >= '0' && ch <= '7' {
+        string.push(closing);
 
-                let mut octal_character_count = 0;
+        Some(string)
+    }
 
-                for byte in &self.remaining[1..] {
-                    if crate::Base2_16::is_oct(*byte) {
-                        octal_character_count += 1;
-                    } else {
-                        break;
-                    }
-                }
+    fn escape(string: & mut String, unit: &Unit, variant: &Variant, quote: char, after_question_mark: bool, next: Option<char>) {
+        use Variant::*;
 
-                octal_character_count = if octal_character_count > 3 {
-                    3
-                } else {
-                    octal_character_count
+        let character = match *unit {
+            Unit::Character(character) => character,
+            Unit::Byte(byte) => {
+                let escape = match variant {
+                    C => Self::numeric_escape(byte, next, true),
+                    Python | Bash => Self::numeric_escape(byte, next, false),
+                    _ => format!("\\x{:02x}", byte),
                 };
 
-                let advanceby = octal_character_count+1;
+                string.push_str(&escape);
+                return;
+            }
+        };
 
-                let slice = &self.remaining[0..advanceby];
-                self.remaining = &self.remaining[advanceby..];
-                slice
+        let escape = match (character, variant) {
+            ('`', PowerShell) => Some("``"),
+            ('$', PowerShell) => Some("`$"),
+            ('"', PowerShell) => Some("`\""),
+            ('\0', PowerShell) => Some("`0"),
+            ('\x07', PowerShell) => Some("`a"),
+            ('\x08', PowerShell) => Some("`b"),
+            ('\x1B', PowerShell) => Some("`e"),
+            ('\x0C', PowerShell) => Some("`f"),
+            ('\n', PowerShell) => Some("`n"),
+            ('\r', PowerShell) => Some("`r"),
+            ('\t', PowerShell) => Some("`t"),
+            ('\x0B', PowerShell) => Some("`v"),
+            (_, PowerShell) => None,
+            ('\\', _) => Some("\\\\"),
+            ('"', _) if quote == '"' => Some("\\\""),
+            ('\'', _) if quote == '\'' => Some("\\'"),
+            //Two question marks could start a trigraph
+            ('?', C) if after_question_mark => Some("\\?"),
+            ('\n', _) => Some("\\n"),
+            ('\r', _) => Some("\\r"),
+            ('\t', _) => Some("\\t"),
+            ('\0', Rust | CSharp) => Some("\\0"),
+            ('\0', JavaScript) if !next.is_some_and(|x| x.is_ascii_digit()) => Some("\\0"),
+            ('\x07', C | Python | CSharp | Go | Bash) => Some("\\a"),
+            ('\x08', C | Python | Json | JavaScript | Java | CSharp | Go | Bash) => Some("\\b"),
+            ('\x0C', C | Python | Json | JavaScript | Java | CSharp | Go | Bash) => Some("\\f"),
+            ('\x0B', C | Python | JavaScript | CSharp | Go | Bash) => Some("\\v"),
+            ('\x1B', Bash) => Some("\\e"),
+            _ => None,
+        };
 
-            } else {
-                //Invalid escape sequence
-                self.remaining = &self.remaining[0..0];
-                return Some(Err(()))
-            }
-        } else {
-            let slice = &self.remaining[0..1];
-            self.remaining = &self.remaining[1..];
-            slice
+        if let Some(escape) = escape {
+            string.push_str(escape);
+            return;
+        }
+
+        let code_point = character as u32;
+
+        match variant {
+            //JSON and Java are kept ASCII, with other characters as UTF-16 code units
+            Json | Java if !character.is_ascii() => {
+                for unit in character.encode_utf16(& mut [0; 2]) {
+                    string.push_str(&format!("\\u{:04x}", unit));
+                }
+            },
+            _ if !character.is_control() => string.push(character),
+            C => string.push_str(&Self::numeric_escape(code_point as u8, next, true)),
+            Python => string.push_str(&Self::numeric_escape(code_point as u8, next, false)),
+            //Java's \u escapes are replaced before the string is parsed, so control characters are written in octal
+            Java if next.is_some_and(|x| x.is_digit(8)) => string.push_str(&format!("\\{:03o}", code_point)),
+            Java => string.push_str(&format!("\\{:o}", code_point)),
+            //C#'s \x would take any hex digits after it, so \u is used instead
+            Json | CSharp => string.push_str(&format!("\\u{:04x}", code_point)),
+            PowerShell => string.push_str(&format!("`u{{{:x}}}", code_point)),
+            Rust if character.is_ascii() => string.push_str(&format!("\\x{:02x}", code_point)),
+            Rust => string.push_str(&format!("\\u{{{:x}}}", code_point)),
+            JavaScript => string.push_str(&format!("\\x{:02x}", code_point)),
+            Go if character.is_ascii() => string.push_str(&format!("\\x{:02x}", code_point)),
+            Go => string.push_str(&format!("\\u{:04x}", code_point)),
+            Bash => {
+                let bytes = character.to_string().into_bytes();
+
+                for (index, &byte) in bytes.iter().enumerate() {
+                    string.push_str(&Self::numeric_escape(byte, if index + 1 == bytes.len() { next } else { None }, false));
+                }
+            },
+        }
+    }
+
+    ///The shortest octal or hex escape of a byte. Octal escapes take up to 3 digits and C hex escapes take every hex digit after
+    ///them, so the shorter forms are only used when the next character can't be read as part of the escape
+    fn numeric_escape(byte: u8, next: Option<char>, greedy_hex: bool) -> String {
+        let octal = match next {
+            Some(next) if next.is_digit(8) => format!("\\{:03o}", byte),
+            _ => format!("\\{:o}", byte),
         };
 
-        Some(Ok(Escapes::from(slice)))
+        let hex = match next {
+            Some(next) if greedy_hex && next.is_ascii_hexdigit() => return octal,
+            _ if greedy_hex => format!("\\x{:x}", byte),
+            _ => format!("\\x{:02x}", byte),
+        };
+
+        if hex.len() <= octal.len() {
+            hex
+        } else {
+            octal
+        }
     }
 }
 
-pub struct EscapeSequence;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl EscapeSequence {
-    pub fn decode(string: &str, variant: Variant) -> Result<Vec<u8>, ()> {
-        let mut bytes = Vec::new();
+    fn decode(string: &str, variant: Variant) -> Result<String, ()> {
+        EscapeSequence::decode(string, variant).map(|bytes| String::from_utf8(bytes).unwrap())
+    }
 
-        for escaped in EscapedStringIterator::new(string, variant) {
-            (escaped?).append_bytes(& mut bytes);
+    #[test]
+    fn c_and_python_escapes() {
+        assert_eq!(EscapeSequence::decode("\\x41\\102\\n\\\\\\\"", Variant::C), Ok(b"AB\n\\\"".to_vec()));
+        assert_eq!(EscapeSequence::decode("\\xff\\0", Variant::C), Ok(vec![0xFF, 0]));
+        assert_eq!(decode("\\u263a \\N{SNOWMAN}", Variant::Python), Ok(String::from("\u{263A} \u{2603}")));
+    }
+
+    #[test]
+    fn dialect_escapes() {
+        assert_eq!(decode("\\u{1F600}\\x41\\0", Variant::Rust), Ok(String::from("\u{1F600}A\0")));
+        assert_eq!(decode("\\ud83d\\ude00\\/", Variant::Json), Ok(String::from("\u{1F600}/")));
+        assert_eq!(decode("\\u{1F600}\\ud83d\\ude00\\x41", Variant::JavaScript), Ok(String::from("\u{1F600}\u{1F600}A")));
+        assert_eq!(decode("\\101\\u00e9", Variant::Java), Ok(String::from("A\u{E9}")));
+        assert_eq!(decode("\\U0001F600\\u00e9\\101", Variant::Go), Ok(String::from("\u{1F600}\u{E9}A")));
+        assert_eq!(decode("`raw \\n`", Variant::Go), Ok(String::from("raw \\n")));
+        assert_eq!(decode("$'tab\\there\\e'", Variant::Bash), Ok(String::from("tab\there\x1B")));
+        assert_eq!(decode("``n`t`$`u{263a}", Variant::PowerShell), Ok(String::from("`n\t$\u{263A}")));
+    }
+
+    #[test]
+    fn dialect_rejects_other_escapes() {
+        //Rust's \x only goes up to 7F, JSON has no \x, and a JSON surrogate must be paired
+        assert!(decode("\\xff", Variant::Rust).is_err());
+        assert!(decode("\\x41", Variant::Json).is_err());
+        assert!(decode("\\ud83d", Variant::Json).is_err());
+        assert!(decode("\\q", Variant::Java).is_err());
+        assert!(decode("$'unterminated", Variant::Bash).is_err());
+    }
+
+    #[test]
+    fn dialect_names() {
+        for variant in Variant::ALL.iter() {
+            assert_eq!(Variant::from_name(variant.name()).map(|x| x.name()), Some(variant.name()));
         }
 
-        Ok(bytes)
+        assert!(Variant::Bash.is_used_in("$'a'"));
+        assert!(!Variant::Bash.is_used_in("'a'"));
+        assert!(Variant::PowerShell.is_used_in("`n"));
+        assert!(!Variant::Json.is_used_in("plain"));
+    }
+
+    #[test]
+    fn encode_literals() {
+        let encode = |bytes: & [u8], variant: Variant, quote: &str| EscapeSequence::encode(bytes, variant, quote);
+
+        assert_eq!(encode("a\"b\n\u{E9}".as_bytes(), Variant::C, "\""), Some(String::from("\"a\\\"b\\n\\xc3\\xa9\"")));
+        assert_eq!(encode("\u{1F600}\0".as_bytes(), Variant::Json, "\""), Some(String::from("\"\\ud83d\\ude00\\u0000\"")));
+        assert_eq!(encode("it's \u{E9}".as_bytes(), Variant::Python, "'"), Some(String::from("'it\\'s \u{E9}'")));
+        assert_eq!(encode(&[0xFF, b'a'], Variant::Python, "b'"), Some(String::from("b'\\xffa'")));
+        assert_eq!(encode("$x`".as_bytes(), Variant::PowerShell, "\""), Some(String::from("\"`$x``\"")));
+        assert_eq!(encode(b"raw\\", Variant::Go, "`"), Some(String::from("`raw\\`")));
+
+        //Text literals can't hold invalid UTF-8, except in Go and Bash
+        assert_eq!(encode(&[0xFF], Variant::Rust, "\""), None);
+        assert_eq!(encode(&[0xFF], Variant::Go, "\""), Some(String::from("\"\\xff\"")));
+        assert_eq!(encode(b"a`b", Variant::Go, "`"), None);
     }
 
-    pub fn encode(bytes: & [u8]) -> String {
-        let mut string = String::new();
-
-        for &byte in bytes {
-            if byte == '\\' as u8 {
-                string.push_str("\\\\");
-            } else if byte >= 0x20 && byte <= 0x7E {
-                string.push(byte as char)
-            } else if byte == '\'' as u8 {
-                string.push_str("\\'");
-            } else if byte == '\"' as u8 {
-                string.push_str("\\");
-            } else if byte == 7 {
-                string.push_str("\\a");
-            } else if byte == 8 {
-                string.push_str("\\b");
-            } else if byte == 12 {
-                string.push_str("\\f");
-            } else if byte == '\n' as u8 {
-                string.push_str("\\n");
-            } else if byte == '\r' as u8 {
-                string.push_str("\\r");
-            }else if byte == '\t' as u8 {
-                string.push_str("\\t");
-            }else if byte == 11 {
-                string.push_str("\\v");
-            } else {
-
-                let upper = crate::common::Base2_16::num_to_ascii(byte >> 4, false) as char;
-                let lower = crate::common::Base2_16::num_to_ascii(byte & 0x0F, false) as char;
-
-                string.push_str("\\x");
-                string.push(upper);
-                string.push(lower);
+    #[test]
+    fn encode_avoids_ambiguous_escapes() {
+        //C hex escapes take every hex digit after them, octal escapes up to 3 digits, and two question marks could start a trigraph
+        assert_eq!(EscapeSequence::encode(&[0x01, b'a'], Variant::C, "\""), Some(String::from("\"\\1a\"")));
+        assert_eq!(EscapeSequence::encode(&[0x01, b'7'], Variant::C, "\""), Some(String::from("\"\\0017\"")));
+        assert_eq!(EscapeSequence::encode(b"??=", Variant::C, "\""), Some(String::from("\"?\\?=\"")));
+        assert_eq!(EscapeSequence::encode(&[0, b'1'], Variant::JavaScript, "'"), Some(String::from("'\\x001'")));
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "quote \" ' \\ `$ ?? \0\x01\x07\x1B\t\n\r \u{7F}\u{E9}\u{FFFF}\u{1F600}";
+
+        for variant in Variant::ALL.iter() {
+            for quote in variant.quotes().iter().filter(|quote| !quote.starts_with('b') && **quote != "`") {
+                let encoded = EscapeSequence::encode(text.as_bytes(), variant.clone(), quote).unwrap();
+
+                let body = match variant {
+                    Variant::Bash => encoded.as_str(),
+                    _ => &encoded[quote.len()..encoded.len() - 1],
+                };
+
+                assert_eq!(decode(body, variant.clone()), Ok(String::from(text)), "{} {}", variant.label(quote), encoded);
             }
         }
+    }
+
+    #[test]
+    fn csharp_escapes() {
+        //\x takes 1 to 4 hex digits, \u is a UTF-16 code unit and \U a code point, and there are no octal escapes
+        assert_eq!(decode("\\x41BC\\x41 \\ud83d\\ude00\\U0001F600", Variant::CSharp), Ok(String::from("\u{41BC}A \u{1F600}\u{1F600}")));
+        assert_eq!(decode("\\a\\e\\0\\v", Variant::CSharp), Ok(String::from("\x07\x1B\0\x0B")));
+        assert!(decode("\\101", Variant::CSharp).is_err());
+        assert!(decode("\\x", Variant::CSharp).is_err());
+
+        assert_eq!(EscapeSequence::encode(b"\x01A", Variant::CSharp, "\""), Some(String::from("\"\\u0001A\"")));
+    }
 
-        string
+    #[test]
+    fn wide_escapes() {
+        //\x and octal escapes are code points, and \x takes up to 8 hex digits
+        assert_eq!(EscapeSequence::decode_wide("A\\x263a\\101\\u00e9"), Ok(String::from("A\u{263A}A\u{E9}")));
+        assert_eq!(EscapeSequence::decode_wide("\\x0001F600"), Ok(String::from("\u{1F600}")));
+        assert!(EscapeSequence::decode_wide("\\x000001F600").is_err());
+        assert!(EscapeSequence::decode_wide("\\xD800").is_err());
     }
 }
@@ -3,8 +3,20 @@ use half::f16;
 use std::convert::TryInto;
 use std::str::{from_utf8, from_utf8_unchecked};
 use std::net::{SocketAddrV4, SocketAddrV6};
-use std::borrow::Cow;
 use crate::endian::{Endianness};
+use crate::base85::Base85Encoding;
+use crate::compact::{Base45Encoding, Base62Encoding};
+use crate::attachment::AttachmentEncoding;
+use crate::mime::MimeEncoding;
+use crate::armor::{PemEncoding, DataUriEncoding, wrap, PEM_COLUMNS, MIME_COLUMNS};
+use crate::utf::UtfEncoding;
+use crate::codepage::CharacterSet;
+use crate::unicode::Form;
+use crate::entity::{EntityEncoding, Escape};
+use crate::quote::{Quoting, Flavour, Dialect};
+use crate::uri::UrlParser;
+use crate::percent::{PercentEncoding, EncodeSet};
+use crate::idn::{DomainName, Processing};
 
 use ansi_term::{ANSIGenericString, Style, Color};
 
@@ -20,22 +32,48 @@ pub trait FromIR {
 
 impl FromIR for crate::common::Base2_16 {
     fn variants(_ir: & [u8]) -> Option<Vec<Variant>> {
-        //Any set of bytes can be converted to base 2-16
-        Some(vec![Variant("Base 2"), //Variant("Base 3"), Variant("Base 4"), Variant("Base 5"), Variant("Base 6"), Variant("Base 7"),
-             Variant("Base 8"), //Variant("Base 9"),
-             Variant("Base 10"), //Variant("Base 11"), Variant("Base 12"), Variant("Base 13"), Variant("Base 14"), Variant("Base 15"),
-             Variant("Base 16")])
+        //Any set of bytes can be converted to base 2-36. Other bases can be displayed using a custom alphabet, which is added by main
+        let variants = vec![Variant::new("Base 2"), Variant::new("Base 2 padded"),
+                            Variant::new("Base 8"), Variant::new("Base 8 padded"),
+                            Variant::new("Base 10"),
+                            Variant::new("Base 16"), Variant::new("Base 16 padded"),
+                            Variant::new("Base 36")];
+
+        Some(variants)
     }
 
     fn encode(ir: & [u8], variant: Variant) -> ANSIGenericString<str> {
         let base = Self::get_base(&variant);
 
-        let mut base_n_list = convert_base::Convert::new(256, base).convert::<u8, u8>(ir.as_ref());
+        //Padded variants write the bytes in order, rather than as a little endian number
+        let bytes: Vec<u8> = if Self::is_padded(&variant) { ir.iter().rev().copied().collect() } else { Vec::from(ir) };
+
+        let mut base_n_list = convert_base::Convert::new(256, base).convert::<u8, u8>(&bytes);
+
+        //Remove leading zeros, keeping at least one digit
+        while base_n_list.len() > 1 && base_n_list.last() == Some(&0) {
+            base_n_list.pop();
+        }
+
+        //Padded variants show every byte, including leading zero bytes
+        if let Some(bits) = Self::bits_per_digit(base).filter(|_| Self::is_padded(&variant)) {
+            let width = (ir.len() as u64 * 8).div_ceil(bits) as usize;
+
+            while base_n_list.len() < width {
+                base_n_list.push(0);
+            }
+        }
+
+        let alphabet = if Self::is_custom(&variant) { Self::custom_alphabet(&variant) } else { Vec::new() };
 
         let mut string = String::new();
 
         for byte in base_n_list.iter_mut() {
-            string.insert(0, Self::num_to_ascii(*byte, true) as char)
+            if Self::is_custom(&variant) {
+                string.insert(0, alphabet[*byte as usize])
+            } else {
+                string.insert(0, Self::num_to_ascii(*byte, true) as char)
+            }
         }
 
         Style::default().paint(string)
@@ -47,26 +85,26 @@ impl FromIR for crate::common::FixedFloat {
         let len = ir.as_ref().len();
 
         let (mut variants, float) = match len {
-            2 => (vec![Variant("16-bit"), Variant("16-bit mantissa/exponent")], f16::from_le_bytes(ir.as_ref().try_into().unwrap()).to_f64()),
-            4 => (vec![Variant("32-bit"), Variant("32-bit mantissa/exponent")], f32::from_le_bytes(ir.as_ref().try_into().unwrap()) as f64),
-            8 => (vec![Variant("64-bit"), Variant("64-bit mantissa/exponent")], f64::from_le_bytes(ir.as_ref().try_into().unwrap())),
+            2 => (vec![Variant::new("16-bit"), Variant::new("16-bit mantissa/exponent")], f16::from_le_bytes(ir.as_ref().try_into().unwrap()).to_f64()),
+            4 => (vec![Variant::new("32-bit"), Variant::new("32-bit mantissa/exponent")], f32::from_le_bytes(ir.as_ref().try_into().unwrap()) as f64),
+            8 => (vec![Variant::new("64-bit"), Variant::new("64-bit mantissa/exponent")], f64::from_le_bytes(ir.as_ref().try_into().unwrap())),
             _ => return None,
         };
 
         if !float.is_infinite() && !float.is_nan() {
             match len {
-                2 => variants.push(Variant("16-bit scientific notation")),
-                4 => variants.push(Variant("32-bit scientific notation")),
-                8 => variants.push(Variant("64-bit scientific notation")),
+                2 => variants.push(Variant::new("16-bit scientific notation")),
+                4 => variants.push(Variant::new("32-bit scientific notation")),
+                8 => variants.push(Variant::new("64-bit scientific notation")),
                 _ => {}
             }
 
 
             if float.log10().abs() < 27.0 {
                 match len {
-                    2 => variants.push(Variant("16-bit SI notation")),
-                    4 => variants.push(Variant("32-bit SI notation")),
-                    8 => variants.push(Variant("64-bit SI notation")),
+                    2 => variants.push(Variant::new("16-bit SI notation")),
+                    4 => variants.push(Variant::new("32-bit SI notation")),
+                    8 => variants.push(Variant::new("64-bit SI notation")),
                     _ => {}
                 }
             }
@@ -105,17 +143,17 @@ impl FromIR for crate::common::FixedInt {
         let len = ir.as_ref().len();
 
         match len {
-            1 => Some(vec![Variant("u8"), Variant("i8")]),
-            2 => Some(vec![Variant("u16"), Variant("i16")]),
-            4 => Some(vec![Variant("u32"), Variant("i32")]),
-            8 => Some(vec![Variant("u64"), Variant("i64")]),
-            16 => Some(vec![Variant("u128"), Variant("i128")]),
+            1 => Some(vec![Variant::new("u8"), Variant::new("i8")]),
+            2 => Some(vec![Variant::new("u16"), Variant::new("i16")]),
+            4 => Some(vec![Variant::new("u32"), Variant::new("i32")]),
+            8 => Some(vec![Variant::new("u64"), Variant::new("i64")]),
+            16 => Some(vec![Variant::new("u128"), Variant::new("i128")]),
             _ => None,
         }
     }
 
     fn encode(ir: & [u8], variant: Variant) -> ANSIGenericString<str> {
-        Style::default().paint(match variant.0 {
+        Style::default().paint(match variant.0.as_ref() {
             "u8" => u8::from_le_bytes(ir.as_ref().try_into().unwrap()).to_string(),
             "i8" => i8::from_le_bytes(ir.as_ref().try_into().unwrap()).to_string(),
             "u16" => u16::from_le_bytes(ir.as_ref().try_into().unwrap()).to_string(),
@@ -136,7 +174,7 @@ impl FromIR for crate::common::DateTime {
         let len = ir.as_ref().len();
 
         match len {
-            4 => Some(vec![Variant("32-bit rfc2822"), Variant("32-bit rfc3339")]),
+            4 => Some(vec![Variant::new("32-bit rfc2822"), Variant::new("32-bit rfc3339")]),
             8 => {
 
                 //Not all combinations of 64-bits result in a valid date, so we
@@ -145,7 +183,7 @@ impl FromIR for crate::common::DateTime {
                 let be_timestamp = i64::from_be_bytes(ir.as_ref().try_into().unwrap());
 
                 if chrono::NaiveDateTime::from_timestamp_opt(le_timestamp, 0).is_some() || chrono::NaiveDateTime::from_timestamp_opt(be_timestamp, 0).is_some() {
-                    Some(vec![Variant("64-bit rfc2822"), Variant("64-bit rfc3339")])
+                    Some(vec![Variant::new("64-bit rfc2822"), Variant::new("64-bit rfc3339")])
                 } else {
                     None
                 }
@@ -183,16 +221,43 @@ impl FromIR for crate::common::DateTime {
 impl FromIR for crate::common::Unicode8 {
     fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
         if from_utf8(ir).is_ok() {
-            for character in unsafe {from_utf8_unchecked(ir).chars()} {
-                if unicode_names2::name(character).is_none() {
-                    return Some(vec![Variant("Literal String")])
-                }
+            let string = unsafe {from_utf8_unchecked(ir)};
+
+            let mut variants = vec![Variant::new("Literal String")];
+            let tables = [Variant::new("Unicode Names"), Variant::new("Security")];
+
+            //Control characters other than whitespace suggest the IR is not text, so the tables are still shown, but last
+            let is_text = crate::unicode::is_text(string);
+
+            if is_text {
+                variants.extend(tables.clone());
+            }
+
+            //Grapheme clusters are only worth showing if some of them are more than one code point
+            if crate::unicode::has_sequences(string) {
+                variants.push(Variant::new("Grapheme Clusters"));
             }
 
-            Some(vec![Variant("Literal String"), Variant("Unicode Names")])
+            if !is_text {
+                variants.extend(tables);
+            }
+
+            Some(variants)
 
         } else {
-            None
+            //Invalid UTF-8 is still shown, with each invalid sequence marked
+            let mut variants = vec![Variant::new("Diagnostics"), Variant::new("Lossy")];
+
+            //Surrogates and the 2 byte NUL are invalid UTF-8, so CESU-8 and Modified UTF-8 are only shown if the IR is not UTF-8
+            if UtfEncoding::decode(ir, crate::utf::Variant::Cesu8).is_ok() {
+                variants.push(Variant::new("CESU-8"));
+            }
+
+            if UtfEncoding::decode(ir, crate::utf::Variant::ModifiedUtf8).is_ok() {
+                variants.push(Variant::new("Modified UTF-8"));
+            }
+
+            Some(variants)
         }
     }
 
@@ -203,34 +268,189 @@ impl FromIR for crate::common::Unicode8 {
                 Style::default().paint(String::from(from_utf8_unchecked(ir)))
             }
         } else if variant.0 == "Unicode Names" {
-            let mut string = String::from("[");
+            let unicode = unsafe {
+                from_utf8_unchecked(ir)
+            };
+
+            //The table starts on a new line so that its columns line up
+            Style::default().paint(format!("\n{}", crate::unicode::table(unicode)))
+        } else if variant.0 == "Security" {
+            let unicode = unsafe {
+                from_utf8_unchecked(ir)
+            };
 
+            Style::default().paint(format!("\n{}", crate::unicode::security_report(unicode)))
+        } else if variant.0 == "Grapheme Clusters" {
             let unicode = unsafe {
                 from_utf8_unchecked(ir)
             };
 
-            for character in unicode.chars() {
+            Style::default().paint(format!("\n{}", crate::unicode::grapheme_breakdown(unicode)))
+        } else if variant.0 == "Diagnostics" {
+            let mut string = String::new();
+            let mut index = 0;
+
+            //Valid text is shown as it is, and each invalid sequence is replaced by its bytes, offset and reason
+            for invalid in UtfEncoding::diagnose_utf8(ir) {
+                string.push_str(from_utf8(&ir[index..invalid.offset]).unwrap());
+
+                let bytes: Vec<_> = ir[invalid.offset..invalid.offset + invalid.length].iter().map(|byte| format!("{:02X}", byte)).collect();
+
+                string.push_str(&Color::Red.paint(format!("[{} at {}: {}]", bytes.join(" "), invalid.offset, invalid.reason)).to_string());
 
-                string.push_str(&unicode_names2::name(character).unwrap().to_string());
-                string.push_str(" '");
-                string.push(character);
-                string.push_str("', ")
+                index = invalid.offset + invalid.length;
             }
 
+            string.push_str(from_utf8(&ir[index..]).unwrap());
+
             Style::default().paint(string)
+        } else if variant.0 == "Lossy" {
+            let lossy = String::from_utf8_lossy(ir);
+
+            let replacement = Color::Red.paint(char::REPLACEMENT_CHARACTER.to_string()).to_string();
+
+            Style::default().paint(lossy.replace(char::REPLACEMENT_CHARACTER, &replacement))
+        } else if variant.0 == "CESU-8" {
+            Style::default().paint(UtfEncoding::decode(ir, crate::utf::Variant::Cesu8).unwrap())
+        } else if variant.0 == "Modified UTF-8" {
+            Style::default().paint(UtfEncoding::decode(ir, crate::utf::Variant::ModifiedUtf8).unwrap())
         } else {
             panic!("Invalid variant in FromIR Unicode8");
         }
     }
 }
 
+impl FromIR for crate::common::Normalization {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        match from_utf8(ir) {
+            Ok(text) if !text.is_empty() && crate::unicode::is_text(text) => Some(vec![Variant::new("NFC"), Variant::new("NFD"), Variant::new("NFKC"), Variant::new("NFKD"), Variant::new("Case folded")]),
+            _ => None,
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        let text = from_utf8(ir).unwrap();
+
+        let form = match variant.0.as_ref() {
+            "NFC" => Form::Nfc,
+            "NFD" => Form::Nfd,
+            "NFKC" => Form::Nfkc,
+            "NFKD" => Form::Nfkd,
+            "Case folded" => Form::CaseFold,
+            _ => panic!("Invalid variant in FromIR Normalization"),
+        };
+
+        let normalized = crate::unicode::normalize(text, form);
+
+        let difference = if normalized == text { "same as input" } else { "differs from input" };
+
+        Style::default().paint(format!("{} ({} bytes, {})", normalized, normalized.len(), difference))
+    }
+}
+
+impl FromIR for crate::common::Unicode16 {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        let mut variants = Vec::new();
+
+        //A byte order mark gives the byte order, and is not part of the text
+        if ir.starts_with(&UtfEncoding::bom(crate::utf::Variant::Utf16Le)) && UtfEncoding::decode(&ir[2..], crate::utf::Variant::Utf16Le).is_ok() {
+            variants.push(Variant::new("BOM little endian"));
+        } else if ir.starts_with(&UtfEncoding::bom(crate::utf::Variant::Utf16Be)) && UtfEncoding::decode(&ir[2..], crate::utf::Variant::Utf16Be).is_ok() {
+            variants.push(Variant::new("BOM big endian"));
+        }
+
+        if UtfEncoding::decode(ir, crate::utf::Variant::Utf16Le).is_ok() {
+            variants.push(Variant::new("Little endian"));
+        }
+
+        if UtfEncoding::decode(ir, crate::utf::Variant::Utf16Be).is_ok() {
+            variants.push(Variant::new("Big endian"));
+        }
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        Style::default().paint(match variant.0.as_ref() {
+            "BOM little endian" => UtfEncoding::decode(&ir[2..], crate::utf::Variant::Utf16Le).unwrap(),
+            "BOM big endian" => UtfEncoding::decode(&ir[2..], crate::utf::Variant::Utf16Be).unwrap(),
+            "Little endian" => UtfEncoding::decode(ir, crate::utf::Variant::Utf16Le).unwrap(),
+            "Big endian" => UtfEncoding::decode(ir, crate::utf::Variant::Utf16Be).unwrap(),
+            _ => panic!("Invalid variant in FromIR Unicode16"),
+        })
+    }
+}
+
+impl FromIR for crate::common::Unicode32 {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        let mut variants = Vec::new();
+
+        //A byte order mark gives the byte order, and is not part of the text
+        if ir.starts_with(&UtfEncoding::bom(crate::utf::Variant::Utf32Le)) && UtfEncoding::decode(&ir[4..], crate::utf::Variant::Utf32Le).is_ok() {
+            variants.push(Variant::new("BOM little endian"));
+        } else if ir.starts_with(&UtfEncoding::bom(crate::utf::Variant::Utf32Be)) && UtfEncoding::decode(&ir[4..], crate::utf::Variant::Utf32Be).is_ok() {
+            variants.push(Variant::new("BOM big endian"));
+        }
+
+        if UtfEncoding::decode(ir, crate::utf::Variant::Utf32Le).is_ok() {
+            variants.push(Variant::new("Little endian"));
+        }
+
+        if UtfEncoding::decode(ir, crate::utf::Variant::Utf32Be).is_ok() {
+            variants.push(Variant::new("Big endian"));
+        }
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        Style::default().paint(match variant.0.as_ref() {
+            "BOM little endian" => UtfEncoding::decode(&ir[4..], crate::utf::Variant::Utf32Le).unwrap(),
+            "BOM big endian" => UtfEncoding::decode(&ir[4..], crate::utf::Variant::Utf32Be).unwrap(),
+            "Little endian" => UtfEncoding::decode(ir, crate::utf::Variant::Utf32Le).unwrap(),
+            "Big endian" => UtfEncoding::decode(ir, crate::utf::Variant::Utf32Be).unwrap(),
+            _ => panic!("Invalid variant in FromIR Unicode32"),
+        })
+    }
+}
+
+impl FromIR for crate::common::Codepage {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        //Every codepage that decodes the bytes is shown, with the most likely ones first
+        let variants: Vec<_> = CharacterSet::ranked(ir).into_iter()
+            .map(|(names, _)| Variant::from_string(CharacterSet::describe(&names)))
+            .collect();
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        match CharacterSet::find(&variant.0) {
+            Some(codepage) => Style::default().paint(codepage.decode(ir).unwrap()),
+            None => panic!("Invalid variant in FromIR Codepage"),
+        }
+    }
+}
+
 impl FromIR for crate::common::IpV4 {
     fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
         let len = ir.as_ref().len();
 
         match len {
-            4 => Some(vec![Variant("without port")]),
-            6 => Some(vec![Variant("with port")]),
+            4 => Some(vec![Variant::new("without port")]),
+            6 => Some(vec![Variant::new("with port")]),
             _ => None,
         }
     }
@@ -239,7 +459,7 @@ impl FromIR for crate::common::IpV4 {
 
         let ip = std::net::Ipv4Addr::from(u32::from_le_bytes((&ir[0..4]).try_into().unwrap()));
 
-        Style::default().paint(match variant.0 {
+        Style::default().paint(match variant.0.as_ref() {
             "with port" => {
                 let port = u16::from_le_bytes((&ir[4..6]).try_into().unwrap());
 
@@ -259,8 +479,8 @@ impl FromIR for crate::common::IpV6 {
         let len = ir.as_ref().len();
 
         match len {
-            16 => Some(vec![Variant("without port")]),
-            18 => Some(vec![Variant("with port")]),
+            16 => Some(vec![Variant::new("without port")]),
+            18 => Some(vec![Variant::new("with port")]),
             _ => None,
         }
     }
@@ -269,7 +489,7 @@ impl FromIR for crate::common::IpV6 {
 
         let ip = std::net::Ipv6Addr::from(u128::from_le_bytes((&ir[0..16]).try_into().unwrap()));
 
-        Style::default().paint(match variant.0 {
+        Style::default().paint(match variant.0.as_ref() {
             "with port" => {
                 let port = u16::from_le_bytes((&ir[16..18]).try_into().unwrap());
 
@@ -286,7 +506,7 @@ impl FromIR for crate::common::IpV6 {
 
 impl FromIR for crate::common::Base91 {
     fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
-        Some(vec![Variant("")])
+        Some(vec![Variant::new("")])
     }
 
     fn encode(ir: &[u8], _variant: Variant) -> ANSIGenericString<str> {
@@ -297,14 +517,24 @@ impl FromIR for crate::common::Base91 {
 }
 
 impl FromIR for crate::common::Base85 {
-    fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
-        Some(vec![Variant("z85"), Variant("ascii85")])
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        let mut variants = vec![Variant::new("z85"), Variant::new("ascii85"), Variant::new("Adobe ascii85"), Variant::new("btoa")];
+
+        //RFC 1924 only encodes 128-bit IPv6 addresses
+        if ir.len() == 16 {
+            variants.push(Variant::new("RFC 1924"));
+        }
+
+        Some(variants)
     }
 
     fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
-        Style::default().paint(match variant.0 {
+        Style::default().paint(match variant.0.as_ref() {
             "z85" => z85::encode(ir),
-             "ascii85" => ascii85::encode(ir),
+            "ascii85" => Base85Encoding::encode(ir, crate::base85::Variant::Ascii85),
+            "Adobe ascii85" => Base85Encoding::encode(ir, crate::base85::Variant::Adobe),
+            "btoa" => Base85Encoding::encode(ir, crate::base85::Variant::Btoa),
+            "RFC 1924" => Base85Encoding::encode(ir, crate::base85::Variant::Rfc1924),
             _ => panic!("Invalid variant in FromIR Base85")
         })
     }
@@ -312,34 +542,163 @@ impl FromIR for crate::common::Base85 {
 
 impl FromIR for crate::common::Base64 {
     fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
-        Some(vec![Variant("Bcrypt"),
-             Variant("BinHex"),
-             Variant("crypt"),
-             Variant("IMAP UTF-7"),
-             Variant("Standard"),
-             Variant("Standard no padding"),
-             Variant("URL-safe"),
-             Variant("URL-safe no padding"),])
+        Some(vec![Variant::new("Bcrypt"),
+             Variant::new("BinHex"),
+             Variant::new("crypt"),
+             Variant::new("IMAP UTF-7"),
+             Variant::new("Standard"),
+             Variant::new("Standard no padding"),
+             Variant::new("URL-safe"),
+             Variant::new("URL-safe no padding"),
+             Variant::new("Standard 64 columns"),
+             Variant::new("Standard 76 columns (MIME)"),])
     }
 
     fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
-        Style::default().paint(match variant.0 {
+        Style::default().paint(match variant.0.as_ref() {
             "Bcrypt" => base64::encode_config(ir, base64::BCRYPT),
             "BinHex" => base64::encode_config(ir, base64::BINHEX),
             "crypt" => base64::encode_config(ir, base64::CRYPT),
             "IMAP UTF-7" => base64::encode_config(ir, base64::IMAP_MUTF7),
             "Standard" => base64::encode_config(ir, base64::STANDARD),
             "Standard no padding" => base64::encode_config(ir, base64::STANDARD_NO_PAD),
-            "URL-safe" => base64::encode_config(ir, base64::STANDARD_NO_PAD),
+            "URL-safe" => base64::encode_config(ir, base64::URL_SAFE),
             "URL-safe no padding" => base64::encode_config(ir, base64::URL_SAFE_NO_PAD),
+            "Standard 64 columns" => wrap(&base64::encode_config(ir, base64::STANDARD), PEM_COLUMNS),
+            "Standard 76 columns (MIME)" => wrap(&base64::encode_config(ir, base64::STANDARD), MIME_COLUMNS),
             _ => panic!("Invalid variant in FromIr Base64"),
         })
     }
 }
 
+impl FromIR for crate::common::Base58 {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        let mut variants = vec![Variant::new("Bitcoin"), Variant::new("Ripple"), Variant::new("Flickr")];
+
+        //Base58Check needs at least a version byte
+        if !ir.is_empty() {
+            variants.push(Variant::new("Bitcoin Check"));
+            variants.push(Variant::new("Ripple Check"));
+            variants.push(Variant::new("Check version/payload"));
+        }
+
+        Some(variants)
+    }
+
+    ///Base58Check variants treat the first byte of the IR as the version byte
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        if variant.0 == "Check version/payload" {
+            let payload: String = ir[1..].iter().map(|byte| format!("{:02x}", byte)).collect();
+
+            return Style::default().paint(format!("version: 0x{:02x}, payload ({} byte(s)): {}", ir[0], ir.len() - 1, payload));
+        }
+
+        let builder = bs58::encode(ir).with_alphabet(crate::common::Base58::get_alphabet(&variant));
+
+        Style::default().paint(if crate::common::Base58::is_check(&variant) {
+            builder.with_check().into_string()
+        } else {
+            builder.into_string()
+        })
+    }
+}
+
+impl FromIR for crate::common::Base45 {
+    fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
+        Some(vec![Variant::new("")])
+    }
+
+    fn encode(ir: &[u8], _variant: Variant) -> ANSIGenericString<str> {
+        Style::default().paint(Base45Encoding::encode(ir))
+    }
+}
+
+impl FromIR for crate::common::Base62 {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        let mut variants = vec![Variant::new("Standard")];
+
+        //KSUIDs are always 20 bytes
+        if ir.len() == 20 {
+            variants.push(Variant::new("KSUID"));
+        }
+
+        Some(variants)
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        Style::default().paint(match variant.0.as_ref() {
+            "Standard" => Base62Encoding::encode(ir, crate::compact::Variant::Standard),
+            "KSUID" => Base62Encoding::encode(ir, crate::compact::Variant::Ksuid),
+            _ => panic!("Invalid variant in FromIR Base62"),
+        })
+    }
+}
+
+impl FromIR for crate::common::Attachment {
+    fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
+        Some(vec![Variant::new("uuencode"), Variant::new("xxencode"), Variant::new("yEnc (ISO-8859-1)"), Variant::new("BinHex 4.0")])
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        Style::default().paint(match variant.0.as_ref() {
+            "uuencode" => AttachmentEncoding::encode(ir, crate::attachment::Variant::Uuencode),
+            "xxencode" => AttachmentEncoding::encode(ir, crate::attachment::Variant::Xxencode),
+            "yEnc (ISO-8859-1)" => AttachmentEncoding::encode(ir, crate::attachment::Variant::Yenc),
+            "BinHex 4.0" => AttachmentEncoding::encode(ir, crate::attachment::Variant::BinHex),
+            _ => panic!("Invalid variant in FromIR Attachment"),
+        })
+    }
+}
+
+impl FromIR for crate::common::Mime {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        //Encoded-words are labelled as UTF-8, so they are only shown for valid UTF-8
+        if std::str::from_utf8(ir).is_ok() {
+            Some(vec![Variant::new("Quoted-printable"), Variant::new("Encoded-word B"), Variant::new("Encoded-word Q")])
+        } else {
+            Some(vec![Variant::new("Quoted-printable")])
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        Style::default().paint(match variant.0.as_ref() {
+            "Quoted-printable" => MimeEncoding::encode(ir, crate::mime::Variant::QuotedPrintable),
+            "Encoded-word B" => MimeEncoding::encode(ir, crate::mime::Variant::EncodedWordB),
+            "Encoded-word Q" => MimeEncoding::encode(ir, crate::mime::Variant::EncodedWordQ),
+            _ => panic!("Invalid variant in FromIR Mime"),
+        })
+    }
+}
+
+impl FromIR for crate::common::Pem {
+    ///The variant is the label, which is the label of the input if it was a PEM document
+    fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
+        Some(vec![Variant::new(Self::DEFAULT_LABEL)])
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        Style::default().paint(PemEncoding::encode(&variant.0, ir))
+    }
+}
+
+impl FromIR for crate::common::DataUri {
+    ///The variants are the media type and the encoding, where the media type is the input's if it was a data URI
+    fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
+        Some(Self::encodings(Self::DEFAULT_MEDIA_TYPE))
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        Style::default().paint(match variant.0.rsplit_once(", ") {
+            Some((media_type, "base64")) => DataUriEncoding::encode(media_type, ir, true),
+            Some((media_type, "percent-encoded")) => DataUriEncoding::encode(media_type, ir, false),
+            _ => panic!("Invalid variant in FromIR DataUri"),
+        })
+    }
+}
+
 impl FromIR for crate::common::ByteList {
     fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
-        Some(vec![Variant("")])
+        Some(vec![Variant::new("")])
     }
 
     fn encode(ir: &[u8], _variant: Variant) -> ANSIGenericString<str> {
@@ -350,7 +709,7 @@ impl FromIR for crate::common::ByteList {
 impl FromIR for crate::common::UUID {
     fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
         if uuid::Uuid::from_slice(ir).is_ok() {
-            Some(vec![Variant("")])
+            Some(vec![Variant::new("")])
         } else {
             None
         }
@@ -366,55 +725,238 @@ impl FromIR for crate::common::UUID {
 }
 
 impl FromIR for crate::common::EscapedString {
-    fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
-        Some(vec![Variant("")])
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        //Byte literals are only given when the bytes are not ASCII, as they would be the same as the text literal
+        let is_ascii = ir.is_ascii();
+
+        let mut variants = Vec::new();
+
+        for dialect in crate::escape::Variant::ALL.iter() {
+            for quote in dialect.quotes().iter().filter(|quote| !(is_ascii && quote.starts_with('b'))) {
+                if crate::escape::EscapeSequence::encode(ir, dialect.clone(), quote).is_some() {
+                    variants.push(Variant::from_string(dialect.label(quote)));
+                }
+            }
+        }
+
+        Some(variants)
     }
 
     fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
-        if variant.0 == "" {
-            Style::default().paint(crate::escape::EscapeSequence::encode(ir))
+        for dialect in crate::escape::Variant::ALL.iter() {
+            for quote in dialect.quotes() {
+                if dialect.label(quote) == variant.0 {
+                    return Style::default().paint(crate::escape::EscapeSequence::encode(ir, dialect.clone(), quote).unwrap());
+                }
+            }
+        }
+
+        panic!("Invalid variant in FromIR EscapedString");
+    }
+}
+
+impl FromIR for crate::common::HtmlEntities {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        let text = from_utf8(ir).ok()?;
+
+        //Text that needs no escaping is left out
+        if EntityEncoding::encode(text, Escape::Named) == text {
+            return None;
+        }
+
+        let mut variants = vec![Variant::new("Minimal"), Variant::new("Named"), Variant::new("Decimal"), Variant::new("Hex")];
+
+        if text.chars().all(EntityEncoding::is_xml_character) {
+            variants.push(Variant::new("XML"));
+        }
+
+        Some(variants)
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        let text = from_utf8(ir).unwrap();
+
+        let escape = match variant.0.as_ref() {
+            "Minimal" => Escape::Minimal,
+            "Named" => Escape::Named,
+            "Decimal" => Escape::Decimal,
+            "Hex" => Escape::Hex,
+            "XML" => Escape::Xml,
+            _ => panic!("Invalid variant in FromIR HtmlEntities"),
+        };
+
+        Style::default().paint(EntityEncoding::encode(text, escape))
+    }
+}
+
+impl FromIR for crate::common::RegexLiteral {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        let text = from_utf8(ir).ok()?;
+
+        //Flavours that don't need to escape anything would just repeat the text
+        let variants: Vec<_> = Flavour::ALL.iter()
+            .filter(|flavour| Quoting::escape_regex(text, (*flavour).clone()) != text)
+            .map(|flavour| Variant::new(flavour.name()))
+            .collect();
+
+        if variants.is_empty() {
+            None
         } else {
-            panic!("Invalid variant in FromIR EscapedString");
+            Some(variants)
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        match Flavour::from_name(&variant.0) {
+            Some(flavour) => Style::default().paint(Quoting::escape_regex(from_utf8(ir).unwrap(), flavour)),
+            None => panic!("Invalid variant in FromIR RegexLiteral"),
         }
     }
 }
 
-impl FromIR for crate::common::UrlEncode {
-    fn variants(_ir: &[u8]) -> Option<Vec<Variant>> {
-        Some(vec![Variant("")])
+impl FromIR for crate::common::SqlString {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        Some(Dialect::ALL.iter()
+            .filter(|dialect| Quoting::quote_sql(ir, (*dialect).clone()).is_some())
+            .map(|dialect| Variant::new(dialect.name()))
+            .collect())
     }
 
     fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
-        if variant.0 == "" {
-            Style::default().paint(urlencoding::encode_binary(ir).to_string())
+        match Dialect::from_name(&variant.0) {
+            Some(dialect) => Style::default().paint(Quoting::quote_sql(ir, dialect).unwrap()),
+            None => panic!("Invalid variant in FromIR SqlString"),
+        }
+    }
+}
+
+impl FromIR for crate::common::ShellWord {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        //Shell words can't hold a null character
+        let text = from_utf8(ir).ok()?;
+
+        if text.contains('\0') {
+            None
         } else {
-            panic!("Invalid variant in FromIR UrlEncode");
+            Some(vec![Variant::new("")])
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        if variant.0.is_empty() {
+            Style::default().paint(Quoting::quote_shell(from_utf8(ir).unwrap()))
+        } else {
+            panic!("Invalid variant in FromIR ShellWord")
         }
     }
 }
 
-impl FromIR for crate::common::UrlDecode {
+impl FromIR for crate::common::UrlComponents {
     fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
-        if from_utf8(ir).is_ok() {
-            Some(vec![Variant("Legacy"), Variant("RFC 3986")])
+        let text = from_utf8(ir).ok()?;
+        let url = UrlParser::parse(text)?;
+
+        if UrlParser::normalize(&url) == text {
+            Some(vec![Variant::new("Components")])
         } else {
-            None
+            Some(vec![Variant::new("Components"), Variant::new("Normalized")])
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        let url = UrlParser::parse(from_utf8(ir).unwrap()).unwrap();
+
+        match variant.0.as_ref() {
+            //The table starts on a new line so that its columns line up
+            "Components" => Style::default().paint(format!("\n{}", UrlParser::table(&url))),
+            "Normalized" => Style::default().paint(UrlParser::normalize(&url)),
+            _ => panic!("Invalid variant in FromIR UrlComponents"),
+        }
+    }
+}
+
+impl FromIR for crate::common::Punycode {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        //Domains are encoded a label at a time, as IDNA domains
+        match from_utf8(ir) {
+            Ok(text) if !text.is_ascii() && !text.contains('.') && DomainName::encode_punycode(text).is_some() => Some(vec![Variant::new("")]),
+            _ => None,
         }
     }
 
     fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        match variant.0.as_ref() {
+            "" => Style::default().paint(DomainName::encode_punycode(from_utf8(ir).unwrap()).unwrap()),
+            _ => panic!("Invalid variant in FromIR Punycode"),
+        }
+    }
+}
+
+impl FromIR for crate::common::Idna {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        let text = from_utf8(ir).ok()?;
+        let mut conversions = Vec::new();
+
+        if DomainName::has_ace_label(text) {
+            conversions.extend(Processing::ALL.iter()
+                .filter_map(|processing| DomainName::to_unicode(text, processing.clone()).ok().map(|unicode| (processing.label(true), unicode.into_bytes())))
+                .filter(|(_, unicode)| unicode != ir));
+        }
+
+        if DomainName::has_unicode_label(text) {
+            conversions.extend(Processing::ALL.iter()
+                .filter_map(|processing| DomainName::to_ascii(text, processing.clone()).ok().map(|ascii| (processing.label(false), ascii.into_bytes()))));
+        }
 
-        let string = if variant.0 == "RFC 3986" {
-            let string = String::from_utf8_lossy(ir);
-            Cow::from(string.replace("+", "%20"))
+        if conversions.is_empty() {
+            None
         } else {
-            String::from_utf8_lossy(ir)
+            Some(Variant::grouped(conversions))
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        let text = from_utf8(ir).unwrap();
+
+        let (domain, unicode) = match variant.0.split(", ").next().and_then(Processing::from_label) {
+            Some((processing, true)) => {
+                let unicode = DomainName::to_unicode(text, processing).unwrap();
+                (unicode.clone(), unicode)
+            },
+            Some((processing, false)) => (DomainName::to_ascii(text, processing).unwrap(), text.to_string()),
+            None => panic!("Invalid variant in FromIR Idna"),
         };
 
-        if variant.0 == "RFC 3986" || variant.0 == "Legacy" {
-            Style::default().paint(urlencoding::decode(&string).unwrap())
+        //The warnings are for the Unicode form, whichever way the domain was converted
+        let warnings = DomainName::warnings(&unicode);
+
+        if warnings.is_empty() {
+            Style::default().paint(domain)
         } else {
-            panic!("Invalid variant in FromIR UrlDecode");
+            Style::default().paint(format!("{}\n{}", domain, warnings.join("\n")))
+        }
+    }
+}
+
+impl FromIR for crate::common::UrlEncode {
+    fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
+        //Sets that don't encode anything are left out, and sets giving the same text are grouped together
+        let sets: Vec<_> = EncodeSet::ALL.iter()
+            .map(|set| (set.name(), PercentEncoding::encode(ir, set.clone()).into_bytes()))
+            .filter(|(_, encoded)| encoded != ir)
+            .collect();
+
+        if sets.is_empty() {
+            None
+        } else {
+            Some(Variant::grouped(sets))
+        }
+    }
+
+    fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
+        match variant.0.split(", ").next().and_then(EncodeSet::from_name) {
+            Some(set) => Style::default().paint(PercentEncoding::encode(ir, set)),
+            None => panic!("Invalid variant in FromIR UrlEncode"),
         }
     }
 }
@@ -422,10 +964,10 @@ impl FromIR for crate::common::UrlDecode {
 impl FromIR for crate::common::Colour {
     fn variants(ir: &[u8]) -> Option<Vec<Variant>> {
         let variants = match ir.len() {
-            1 => vec![Variant("8-bit color"), Variant("8-bit greyscale"), Variant("8-bit terminal")],
-            2 => vec![Variant("16-bit color")],
-            3 => vec![Variant("24-bit color"), Variant("24-bit rgb"), Variant("24-bit hsl")],
-            4 => vec![Variant("32-bit color")],
+            1 => vec![Variant::new("8-bit color"), Variant::new("8-bit greyscale"), Variant::new("8-bit terminal")],
+            2 => vec![Variant::new("16-bit color")],
+            3 => vec![Variant::new("24-bit color"), Variant::new("24-bit rgb"), Variant::new("24-bit hsl")],
+            4 => vec![Variant::new("32-bit color")],
             _ => return None
         };
 
@@ -434,7 +976,7 @@ impl FromIR for crate::common::Colour {
 
     fn encode(ir: &[u8], variant: Variant) -> ANSIGenericString<str> {
 
-        let colour = match variant.0 {
+        let colour = match variant.0.as_ref() {
             "8-bit color" => {
                 Colour::_8_to_24(*ir.get(0).unwrap())
             },
@@ -457,7 +999,7 @@ impl FromIR for crate::common::Colour {
             _ => panic!("Invalid variant in FromIR Colour")
         };
 
-        match variant.0 {
+        match variant.0.as_ref() {
             "24-bit rgb" => {
                 if let Color::RGB(r, g, b) = colour {
                     Style::default().paint(format!("rgb({}, {}, {})", r, g, b))
@@ -503,4 +1045,22 @@ impl FromIR for crate::common:: {
 
 */
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Unicode8;
+
+    fn names(variants: Option<Vec<Variant>>) -> Vec<String> {
+        variants.unwrap().into_iter().map(|variant| variant.0.into_owned()).collect()
+    }
+
+    #[test]
+    fn unicode8_tables_for_control_characters() {
+        assert_eq!(names(Unicode8::variants(b"text")), ["Literal String", "Unicode Names", "Security"]);
 
+        //Text with control characters still gets the tables, after the other variants
+        assert_eq!(names(Unicode8::variants(b"a\x07b")), ["Literal String", "Unicode Names", "Security"]);
+        assert_eq!(names(Unicode8::variants("\u{1B}e\u{301}".as_bytes())), ["Literal String", "Grapheme Clusters", "Unicode Names", "Security"]);
+        assert!(Unicode8::encode(b"a\x07b", Variant::new("Unicode Names")).to_string().contains("<control-0007>"));
+    }
+}
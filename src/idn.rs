@@ -0,0 +1,219 @@
+use idna::uts46::{Uts46, AsciiDenyList, Hyphens, DnsLength};
+use unicode_script::{UnicodeScript, Script};
+use unicode_security::MixedScript;
+
+/// idn.rs implements internationalised domain names, converting between xn-- labels and Unicode with UTS #46, raw Punycode (RFC 3492)
+/// and the warnings shown for domains that could be mistaken for another domain
+
+#[derive(Debug, Clone)]
+pub enum Processing {
+    Uts46, //UTS #46 as browsers use it, without the STD3 rules, hyphen checks or DNS length limits
+    Strict, //UTS #46 with the STD3 rules, hyphen checks and DNS lengths, which is as close to IDNA 2008 as the idna crate goes
+}
+
+//Longest label allowed in DNS, which raw Punycode is also limited to
+const MAX_LABEL: usize = 63;
+
+impl Processing {
+    pub const ALL: [Processing; 2] = [Processing::Uts46, Processing::Strict];
+
+    pub fn name(&self) -> & 'static str {
+        match self {
+            Processing::Uts46 => "UTS #46",
+            Processing::Strict => "Strict",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|processing| processing.name() == name).cloned()
+    }
+
+    ///Name of the conversion to Unicode or to xn-- labels with this processing, such as 'Unicode (UTS #46)'
+    pub fn label(&self, unicode: bool) -> & 'static str {
+        match (self, unicode) {
+            (Processing::Uts46, true) => "Unicode (UTS #46)",
+            (Processing::Strict, true) => "Unicode (strict)",
+            (Processing::Uts46, false) => "ASCII (UTS #46)",
+            (Processing::Strict, false) => "ASCII (strict)",
+        }
+    }
+
+    ///The processing and direction for a label
+    pub fn from_label(label: &str) -> Option<(Self, bool)> {
+        Self::ALL.iter()
+            .flat_map(|processing| [(processing.clone(), true), (processing.clone(), false)])
+            .find(|(processing, unicode)| processing.label(*unicode) == label)
+    }
+
+    fn options(&self) -> (AsciiDenyList, Hyphens, DnsLength) {
+        match self {
+            Processing::Uts46 => (AsciiDenyList::URL, Hyphens::Allow, DnsLength::Ignore),
+            Processing::Strict => (AsciiDenyList::STD3, Hyphens::Check, DnsLength::Verify),
+        }
+    }
+}
+
+pub struct DomainName;
+
+impl DomainName {
+    ///Returns true if the text is a single word with at least one label starting 'xn--'
+    pub fn has_ace_label(text: &str) -> bool {
+        !text.contains(char::is_whitespace) && text.split('.').any(|label| label.len() > 4 && label.as_bytes()[..4].eq_ignore_ascii_case(b"xn--"))
+    }
+
+    ///Returns true if the text is a single word with a dot or without, that has a character outside ASCII
+    pub fn has_unicode_label(text: &str) -> bool {
+        !text.is_ascii() && !text.contains(char::is_whitespace)
+    }
+
+    ///The domain with each xn-- label decoded, or an error if any label is not valid with the processing
+    pub fn to_unicode(domain: &str, processing: Processing) -> Result<String, ()> {
+        let (deny, hyphens, length) = processing.options();
+
+        let (unicode, result) = Uts46::new().to_unicode(domain.as_bytes(), deny, hyphens);
+
+        //Decoding doesn't check the length, so the length of the encoded domain is checked instead
+        if result.is_err() || (length == DnsLength::Verify && Self::to_ascii(domain, processing).is_err()) {
+            Err(())
+        } else {
+            Ok(unicode.into_owned())
+        }
+    }
+
+    ///The domain with each label that isn't ASCII encoded as an xn-- label
+    pub fn to_ascii(domain: &str, processing: Processing) -> Result<String, ()> {
+        let (deny, hyphens, length) = processing.options();
+
+        Uts46::new().to_ascii(domain.as_bytes(), deny, hyphens, length).map(|ascii| ascii.into_owned()).map_err(|_| ())
+    }
+
+    ///Decode a single label of raw Punycode, without the xn-- prefix. Any ASCII word is valid Punycode, so only labels that decode
+    ///to text in a single script are accepted, and labels without a '-' before the encoded part need a digit in it
+    pub fn decode_punycode(text: &str) -> Option<String> {
+        if text.is_empty() || text.len() > MAX_LABEL || !text.bytes().all(|x| x.is_ascii_lowercase() || x.is_ascii_digit() || x == b'-') {
+            return None;
+        }
+
+        //Words such as 'hello' decode to a few CJK characters, but encoded labels without any ASCII almost always have a digit
+        if !text.contains('-') && !text.bytes().any(|x| x.is_ascii_digit()) {
+            return None;
+        }
+
+        let decoded = idna::punycode::decode_to_string(text)?;
+
+        if decoded.is_ascii() || !crate::unicode::is_text(&decoded) || !decoded.is_single_script() {
+            None
+        } else {
+            Some(decoded)
+        }
+    }
+
+    pub fn encode_punycode(text: &str) -> Option<String> {
+        idna::punycode::encode_str(text)
+    }
+
+    ///Warnings for each label that mixes scripts, has characters that look like those of the rest of the label in another script,
+    ///or is made of characters from another script that look like ASCII
+    pub fn warnings(domain: &str) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        for label in domain.split('.').filter(|label| !label.is_ascii()) {
+            if !label.is_single_script() {
+                let mut scripts: Vec<& 'static str> = Vec::new();
+
+                for script in label.chars().map(|character| UnicodeScript::script(&character)) {
+                    if script != Script::Common && script != Script::Inherited && !scripts.contains(&script.full_name()) {
+                        scripts.push(script.full_name());
+                    }
+                }
+
+                warnings.push(format!("'{}' mixes {} scripts", label, scripts.join(", ")));
+            }
+
+            for finding in crate::unicode::find_suspicious(label) {
+                warnings.push(format!("U+{:04X} {} ({})", finding.character as u32, crate::unicode::name(finding.character), finding.reason));
+            }
+
+            //Labels in a single script that isn't Latin can still look like an ASCII label, such as Cyrillic 'аррӏе'
+            let skeleton: String = unicode_security::skeleton(label).collect();
+
+            if skeleton.is_ascii() && label.chars().all(|character| UnicodeScript::script(&character) != Script::Latin) {
+                warnings.push(format!("'{}' looks like '{}'", label, skeleton));
+            }
+        }
+
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn punycode_known_vectors() {
+        assert_eq!(DomainName::encode_punycode("bücher"), Some(String::from("bcher-kva")));
+        assert_eq!(DomainName::decode_punycode("bcher-kva"), Some(String::from("bücher")));
+
+        //Samples from RFC 3492
+        assert_eq!(DomainName::decode_punycode("ihqwcrb4cv8a8dqg056pqjye"), Some(String::from("他们为什么不说中文")));
+        assert_eq!(DomainName::encode_punycode("他们为什么不说中文"), Some(String::from("ihqwcrb4cv8a8dqg056pqjye")));
+    }
+
+    #[test]
+    fn punycode_rejects_words() {
+        //Plain words decode to a few characters of another script, but have no '-' or digit
+        assert_eq!(DomainName::decode_punycode("hello"), None);
+        assert_eq!(DomainName::decode_punycode("Bcher-kva"), None);
+        assert_eq!(DomainName::decode_punycode(&"a1".repeat(32)), None);
+    }
+
+    #[test]
+    fn label_detection() {
+        assert!(DomainName::has_ace_label("www.xn--bcher-kva.example"));
+        assert!(DomainName::has_ace_label("XN--BCHER-KVA"));
+        assert!(!DomainName::has_ace_label("xn--"));
+        assert!(!DomainName::has_ace_label("xn--a b"));
+
+        assert!(DomainName::has_unicode_label("bücher.example"));
+        assert!(!DomainName::has_unicode_label("bücher example"));
+    }
+
+    #[test]
+    fn uts46_processing() {
+        assert_eq!(DomainName::to_ascii("Bücher.Example", Processing::Uts46), Ok(String::from("xn--bcher-kva.example")));
+        assert_eq!(DomainName::to_unicode("xn--bcher-kva.example", Processing::Uts46), Ok(String::from("bücher.example")));
+
+        //Strict processing applies the STD3 rules, hyphen checks and DNS lengths
+        assert!(DomainName::to_ascii("a_b.example", Processing::Uts46).is_ok());
+        assert!(DomainName::to_ascii("a_b.example", Processing::Strict).is_err());
+        assert!(DomainName::to_ascii("-ab.example", Processing::Strict).is_err());
+        assert!(DomainName::to_unicode(&format!("{}.example", "a".repeat(64)), Processing::Strict).is_err());
+        assert!(DomainName::to_unicode("xn--bcher-kva.example", Processing::Strict).is_ok());
+    }
+
+    #[test]
+    fn processing_labels() {
+        for processing in Processing::ALL.iter() {
+            for unicode in [true, false] {
+                let (found, found_unicode) = Processing::from_label(processing.label(unicode)).unwrap();
+
+                assert_eq!((found.name(), found_unicode), (processing.name(), unicode));
+            }
+        }
+    }
+
+    #[test]
+    fn confusable_warnings() {
+        assert!(DomainName::warnings("bücher.example").is_empty());
+
+        //All Cyrillic, but looks like 'coco'
+        assert_eq!(DomainName::warnings("\u{441}\u{43E}\u{441}\u{43E}.com"), vec![String::from("'\u{441}\u{43E}\u{441}\u{43E}' looks like 'coco'")]);
+
+        //Latin with a Cyrillic 'а'
+        let warnings = DomainName::warnings("p\u{430}ypal.com");
+
+        assert_eq!(warnings[0], "'p\u{430}ypal' mixes Latin, Cyrillic scripts");
+        assert!(warnings[1].starts_with("U+0430 CYRILLIC SMALL LETTER A"));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::escape::EscapeSequence;
+
+/// literal.rs implements string literals copied from source code, with their quotes, prefixes, raw strings and concatenation
+
+#[derive(Debug, Clone)]
+pub enum Language {
+    C, //C and C++, with u8, u, U and L prefixes and C++ raw strings such as R"(..)". u is UTF-16, and U and L are UTF-32, as wchar_t is outside Windows
+    CSharp, //Including verbatim strings, @".."
+    Python, //Single, double and triple quotes, with r, u and b prefixes in any case
+    Rust, //b"..", r#".."# and br".."
+    JavaScript,
+    Json,
+    Java,
+    Go, //Including raw strings in back quotes
+}
+
+impl Language {
+    pub const ALL: [Language; 8] = [Language::C, Language::CSharp, Language::Python, Language::Rust, Language::JavaScript, Language::Json, Language::Java, Language::Go];
+
+    pub fn name(&self) -> & 'static str {
+        match self {
+            Language::C => "C/C++",
+            Language::CSharp => "C#",
+            Language::Python => "Python",
+            Language::Rust => "Rust",
+            Language::JavaScript => "JavaScript",
+            Language::Json => "JSON",
+            Language::Java => "Java",
+            Language::Go => "Go",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|language| language.name() == name).cloned()
+    }
+
+    fn escapes(&self) -> crate::escape::Variant {
+        match self {
+            Language::C => crate::escape::Variant::C,
+            Language::CSharp => crate::escape::Variant::CSharp,
+            Language::Python => crate::escape::Variant::Python,
+            Language::Rust => crate::escape::Variant::Rust,
+            Language::JavaScript => crate::escape::Variant::JavaScript,
+            Language::Json => crate::escape::Variant::Json,
+            Language::Java => crate::escape::Variant::Java,
+            Language::Go => crate::escape::Variant::Go,
+        }
+    }
+
+    fn quotes(&self) -> & 'static [& 'static str] {
+        match self {
+            Language::Python => &["\"\"\"", "'''", "\"", "'"],
+            Language::JavaScript => &["\"", "'"],
+            _ => &["\""],
+        }
+    }
+}
+
+pub struct SourceString;
+
+impl SourceString {
+    ///Decode one or more literals, which C and Python join together when they are next to each other
+    pub fn decode(source: &str, language: Language) -> Result<Vec<u8>, ()> {
+        let mut rest = source.trim();
+        let mut bytes = Vec::new();
+        let mut count = 0;
+
+        while !rest.is_empty() {
+            if count > 0 && !matches!(language, Language::C | Language::Python) {
+                return Err(());
+            }
+
+            let (decoded, remaining) = Self::decode_literal(rest, &language)?;
+
+            bytes.extend(decoded);
+            rest = remaining.trim_start();
+            count += 1;
+        }
+
+        if count == 0 {
+            Err(())
+        } else {
+            Ok(bytes)
+        }
+    }
+
+    //Decode the literal at the start of the source, returning its bytes and the rest of the source
+    fn decode_literal<'t>(source: & 't str, language: &Language) -> Result<(Vec<u8>, & 't str), ()> {
+        let length = source.find(|x: char| !x.is_ascii_alphanumeric() && x != '@').unwrap_or(source.len());
+        let (prefix, rest) = source.split_at(length);
+
+        let (body, rest) = match (language, prefix) {
+            //Wide and Unicode literals are written as little endian code units
+            (Language::C, "u" | "U" | "L") => {
+                let (body, rest) = Self::quoted(rest, language.quotes())?;
+                (Self::code_units(&EscapeSequence::decode_wide(body)?, prefix), rest)
+            },
+            (Language::C, "uR" | "UR" | "LR") => {
+                let (raw, rest) = Self::delimited(rest)?;
+                (Self::code_units(&String::from_utf8(raw).unwrap(), prefix), rest)
+            },
+            (Language::C, "" | "u8") | (Language::CSharp | Language::JavaScript | Language::Json | Language::Java, "") => {
+                let (body, rest) = Self::quoted(rest, language.quotes())?;
+                (EscapeSequence::decode(body, language.escapes())?, rest)
+            },
+            (Language::C, "R" | "u8R") => Self::delimited(rest)?,
+            (Language::CSharp, "@") => Self::verbatim(rest)?,
+            (Language::Python, _) => {
+                let prefix = prefix.to_ascii_lowercase();
+                let (body, rest) = Self::quoted(rest, language.quotes())?;
+                let bytes = prefix.contains('b');
+
+                //Raw strings keep their backslashes, although a backslash still stops a quote from ending the string
+                let decoded = match prefix.as_str() {
+                    "" | "u" => EscapeSequence::decode(body, language.escapes())?,
+                    "b" => EscapeSequence::decode_bytes(body, language.escapes())?,
+                    "r" | "rb" | "br" if !bytes || body.is_ascii() => body.as_bytes().to_vec(),
+                    _ => return Err(()),
+                };
+
+                (decoded, rest)
+            },
+            (Language::Rust, "" | "b") => {
+                let (body, rest) = Self::quoted(rest, language.quotes())?;
+
+                let decoded = if prefix == "b" {
+                    EscapeSequence::decode_bytes(body, language.escapes())?
+                } else {
+                    EscapeSequence::decode(body, language.escapes())?
+                };
+
+                (decoded, rest)
+            },
+            (Language::Rust, "r" | "br") => {
+                let (decoded, rest) = Self::hashed(rest)?;
+
+                if prefix == "br" && !decoded.is_ascii() {
+                    return Err(());
+                }
+
+                (decoded, rest)
+            },
+            //Raw strings in back quotes, without carriage returns
+            (Language::Go, "") if rest.starts_with('`') => {
+                let end = rest[1..].find('`').ok_or(())? + 1;
+                (rest[1..end].replace('\r', "").into_bytes(), &rest[end + 1..])
+            },
+            (Language::Go, "") => {
+                let (body, rest) = Self::quoted(rest, language.quotes())?;
+                (EscapeSequence::decode(body, language.escapes())?, rest)
+            },
+            _ => return Err(()),
+        };
+
+        Ok((body, rest))
+    }
+
+    //The text as the little endian code units of a C literal with the prefix, which are 16 bits for u and 32 bits for U and L
+    fn code_units(text: &str, prefix: &str) -> Vec<u8> {
+        if prefix.starts_with('u') {
+            text.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect()
+        } else {
+            text.chars().flat_map(|character| (character as u32).to_le_bytes()).collect()
+        }
+    }
+
+    //The body of a literal in one of the quotes, up to the first closing quote that is not escaped with a backslash
+    fn quoted<'t>(source: & 't str, quotes: &[&str]) -> Result<(& 't str, & 't str), ()> {
+        let quote = quotes.iter().find(|quote| source.starts_with(**quote)).ok_or(())?;
+        let bytes = source.as_bytes();
+
+        let mut index = quote.len();
+
+        while index < bytes.len() {
+            if bytes[index] == b'\\' {
+                index += 2;
+            } else if bytes[index..].starts_with(quote.as_bytes()) {
+                return Ok((&source[quote.len()..index], &source[index + quote.len()..]));
+            } else {
+                index += 1;
+            }
+        }
+
+        Err(())
+    }
+
+    ///C++ raw strings, R"delimiter(..)delimiter", where the delimiter is up to 16 characters
+    fn delimited(source: &str) -> Result<(Vec<u8>, &str), ()> {
+        let source = source.strip_prefix('"').ok_or(())?;
+        let open = source.find('(').ok_or(())?;
+        let delimiter = &source[..open];
+
+        if delimiter.len() > 16 || delimiter.contains([' ', ')', '\\', '\t', '\n', '"']) {
+            return Err(());
+        }
+
+        let closing = format!("){}\"", delimiter);
+        let end = source[open + 1..].find(&closing).ok_or(())? + open + 1;
+
+        Ok((source.as_bytes()[open + 1..end].to_vec(), &source[end + closing.len()..]))
+    }
+
+    ///C# verbatim strings, @"..", where "" is a quote and there are no escapes
+    fn verbatim(source: &str) -> Result<(Vec<u8>, &str), ()> {
+        let mut rest = source.strip_prefix('"').ok_or(())?;
+        let mut text = String::new();
+
+        loop {
+            let end = rest.find('"').ok_or(())?;
+            text.push_str(&rest[..end]);
+            rest = &rest[end + 1..];
+
+            match rest.strip_prefix('"') {
+                Some(after) => {
+                    text.push('"');
+                    rest = after;
+                },
+                None => return Ok((text.into_bytes(), rest)),
+            }
+        }
+    }
+
+    ///Rust raw strings, r#".."#, closed by a quote followed by as many #s as opened it
+    fn hashed(source: &str) -> Result<(Vec<u8>, &str), ()> {
+        let hashes = source.len() - source.trim_start_matches('#').len();
+        let body = source[hashes..].strip_prefix('"').ok_or(())?;
+
+        let closing = format!("\"{}", "#".repeat(hashes));
+        let end = body.find(&closing).ok_or(())?;
+
+        Ok((body.as_bytes()[..end].to_vec(), &body[end + closing.len()..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(source: &str, language: Language) -> Result<Vec<u8>, ()> {
+        SourceString::decode(source, language)
+    }
+
+    #[test]
+    fn c_prefixes() {
+        assert_eq!(decode("\"a\\n\" \"b\"", Language::C), Ok(b"a\nb".to_vec()));
+        assert_eq!(decode("u8\"\\u00e9\"", Language::C), Ok("\u{E9}".as_bytes().to_vec()));
+
+        //u is little endian UTF-16, and U and L are little endian UTF-32
+        assert_eq!(decode("u\"A\\x263a\"", Language::C), Ok(vec![0x41, 0x00, 0x3A, 0x26]));
+        assert_eq!(decode("u\"\\U0001F600\"", Language::C), Ok(vec![0x3D, 0xD8, 0x00, 0xDE]));
+        assert_eq!(decode("U\"\\x1F600\"", Language::C), Ok(vec![0x00, 0xF6, 0x01, 0x00]));
+        assert_eq!(decode("L\"\\101\"", Language::C), Ok(vec![0x41, 0x00, 0x00, 0x00]));
+    }
+
+    #[test]
+    fn raw_strings() {
+        assert_eq!(decode("R\"x(a)\"b)x\"", Language::C), Ok(b"a)\"b".to_vec()));
+        assert_eq!(decode("uR\"(\\n)\"", Language::C), Ok(vec![0x5C, 0x00, 0x6E, 0x00]));
+        assert_eq!(decode("@\"C:\\path \"\"quoted\"\"\"", Language::CSharp), Ok(b"C:\\path \"quoted\"".to_vec()));
+        assert_eq!(decode("r##\"a \"# b\"##", Language::Rust), Ok(b"a \"# b".to_vec()));
+        assert_eq!(decode("`raw\\n`", Language::Go), Ok(b"raw\\n".to_vec()));
+        assert_eq!(decode("r'\\d+'", Language::Python), Ok(b"\\d+".to_vec()));
+
+        //Delimiters are at most 16 characters
+        assert!(decode("R\"12345678901234567(a)12345678901234567\"", Language::C).is_err());
+    }
+
+    #[test]
+    fn language_literals() {
+        assert_eq!(decode("'''it's''' \"x\"", Language::Python), Ok(b"it'sx".to_vec()));
+        assert_eq!(decode("b'\\xff'", Language::Python), Ok(vec![0xFF]));
+        assert_eq!(decode("b\"\\xff\"", Language::Rust), Ok(vec![0xFF]));
+        assert_eq!(decode("\"\\x41BC\"", Language::CSharp), Ok("\u{41BC}".as_bytes().to_vec()));
+
+        //Only C and Python join adjacent literals, and byte literals must be ASCII
+        assert!(decode("\"a\" \"b\"", Language::Rust).is_err());
+        assert!(decode("b'\u{E9}'", Language::Python).is_err());
+        assert!(decode("\"unterminated", Language::Java).is_err());
+        assert!(decode("x\"a\"", Language::C).is_err());
+    }
+}
@@ -5,10 +5,24 @@ mod fromir;
 mod toir;
 mod escape;
 mod endian;
+mod base85;
+mod compact;
+mod attachment;
+mod mime;
+mod armor;
+mod utf;
+mod codepage;
+mod unicode;
+mod entity;
+mod quote;
+mod literal;
+mod uri;
+mod percent;
+mod idn;
 
 use fromir::FromIR;
 use toir::ToIR;
-use crate::common::{Variant, FixedInt, FixedFloat, Base2_16, DateTime, Unicode8, IpV4, IpV6, Base91, Base64, Base85, ByteList, UUID, EscapedString, UrlEncode, UrlDecode, UnicodeNames};
+use crate::common::{Variant, FixedInt, FixedFloat, Base2_16, DateTime, Unicode8, IpV4, IpV6, Unicode16, Unicode32, Codepage, Normalization, Base91, Base64, Base85, Base58, Base45, Base62, Attachment, Mime, Pem, DataUri, ByteList, UUID, EscapedString, StringLiteral, HtmlEntities, RegexLiteral, SqlString, ShellWord, UrlEncode, UrlDecode, UrlComponents, Punycode, Idna, UnicodeNames, CodePoints};
 
 use colour::{blue, yellow, green, magenta};
 use crate::endian::{Endianness, Endian};
@@ -36,8 +50,80 @@ fn read_without_newline() -> String {
 
 }
 
+type LineTest = fn(& str) -> bool;
+
+type InputVariants = fn(& [u8], Variant) -> Option<Vec<Variant>>;
+
+//Formats that span multiple lines, as a test for the first line, a test for whether all the lines have been read and a description of the
+//line that ends the input
+const MULTILINE_FORMATS: [(LineTest, LineTest, & str); 7] = [
+    (|line| line.starts_with("begin ") && line.split(' ').nth(1).is_some_and(|mode| mode.bytes().all(|x| (b'0'..=b'7').contains(&x))), |input| input.ends_with("\nend"), "'end'"),
+    (|line| line.starts_with("=ybegin "), |input| input.lines().last().is_some_and(|line| line.starts_with("=yend")), "'=yend'"),
+    (|line| line.starts_with("xbtoa Begin"), |input| input.lines().last().is_some_and(|line| line.starts_with("xbtoa End")), "'xbtoa End'"),
+    (|line| line.starts_with(attachment::BINHEX_HEADER), |input| input.matches(':').count() >= 2, "the closing ':'"),
+    (|line| line.starts_with("-----BEGIN "), |input| input.lines().last().is_some_and(|line| line.starts_with("-----END ")), "'-----END'"),
+    //A single line of wrapped base64 is the same length as the lines of a longer one, and any text can follow a soft line break, so only an
+    //empty line can end them
+    (armor::is_wrapped_base64, ends_with_empty_line, "an empty line"),
+    (mime::is_soft_line_break, ends_with_empty_line, "an empty line"),
+];
+
+fn ends_with_empty_line(input: &str) -> bool {
+    input.ends_with('\n')
+}
+
+///Read the input string. If the first line starts a multi-line format, lines are read until the format is complete, or the end of the input
+fn read_input() -> String {
+    let mut input = read_without_newline();
+
+    if let Some((_, is_complete, end)) = MULTILINE_FORMATS.iter().find(|(is_first, _, _)| is_first(&input)) {
+        println!("Reading more lines, up to {}:", end);
+
+        loop {
+            let mut line = String::new();
+
+            if std::io::stdin().read_line(& mut line).expect("Failed to read from stdin") == 0 {
+                break;
+            }
+
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            input.push('\n');
+            input.push_str(line);
+
+            if is_complete(&input) {
+                break;
+            }
+        }
+
+        //The empty line only ends the input, it isn't part of it
+        if input.ends_with('\n') {
+            input.pop();
+        }
+    }
+
+    input
+}
+
+///Read the alphabet following `--alphabet` from the command line arguments, if there is one
+fn alphabet_from_args() -> Result<Option<Vec<char>>, ()> {
+    match std::env::args().skip_while(|arg| arg != "--alphabet").nth(1) {
+        Some(alphabet) => Base2_16::parse_alphabet(&alphabet).map(Some),
+        None => Ok(None),
+    }
+}
+
 fn main() {
 
+    //Validate the custom alphabet before any input is read
+    let alphabet = match alphabet_from_args() {
+        Ok(alphabet) => alphabet,
+        Err(()) => {
+            eprintln!("Custom alphabets must contain between 2 and 64 unique characters");
+            std::process::exit(1);
+        }
+    };
+
     let to_ir: Vec<(fn(& str) -> Option<Vec<Variant>>, & str, fn(& str, Variant) -> Vec<u8>, fn() -> Endianness)> = vec![
         (IpV4::identify, "Ipv4 address", IpV4::decode, IpV4::endianness),
         (IpV6::identify, "Ipv6 address", IpV6::decode, IpV6::endianness),
@@ -45,14 +131,34 @@ fn main() {
         (FixedFloat::identify, "Floats", FixedFloat::decode, FixedFloat::endianness),
         (UUID::identify, "UUID", UUID::decode, UUID::endianness),
         (FixedInt::identify, "Primitive integers", FixedInt::decode, FixedInt::endianness),
-        (Base2_16::identify, "Base 2-16 number", Base2_16::decode, Base2_16::endianness),
+        (Base2_16::identify, "Base 2-36 number", Base2_16::decode, Base2_16::endianness),
         (Base64::identify, "Base64 data", Base64::decode, Base64::endianness),
         (Base85::identify, "Base85 data", Base85::decode, Base85::endianness),
         (Base91::identify, "Base91 data", Base91::decode, Base91::endianness),
+        (Base58::identify, "Base58 data", Base58::decode, Base58::endianness),
+        (Base45::identify, "Base45 data", Base45::decode, Base45::endianness),
+        (Base62::identify, "Base62 data", Base62::decode, Base62::endianness),
+        (Attachment::identify, "Attachment encoding", Attachment::decode, Attachment::endianness),
+        (Mime::identify, "MIME encoding", Mime::decode, Mime::endianness),
+        (Pem::identify, "PEM armor", Pem::decode, Pem::endianness),
+        (DataUri::identify, "Data URI", DataUri::decode, DataUri::endianness),
         (Unicode8::identify, "Unicode 8 string", Unicode8::decode, Unicode8::endianness),
+        (Unicode16::identify, "Unicode 16 string", Unicode16::decode, Unicode16::endianness),
+        (Unicode32::identify, "Unicode 32 string", Unicode32::decode, Unicode32::endianness),
+        (Codepage::identify, "Codepage string", Codepage::decode, Codepage::endianness),
         (ByteList::identify, "Byte list", ByteList::decode, ByteList::endianness),
         (EscapedString::identify, "Escaped sequence", EscapedString::decode, EscapedString::endianness),
+        (StringLiteral::identify, "Source string literal", StringLiteral::decode, StringLiteral::endianness),
+        (HtmlEntities::identify, "HTML entities", HtmlEntities::decode, HtmlEntities::endianness),
+        (RegexLiteral::identify, "Regex literal", RegexLiteral::decode, RegexLiteral::endianness),
+        (SqlString::identify, "SQL string", SqlString::decode, SqlString::endianness),
+        (ShellWord::identify, "Shell word", ShellWord::decode, ShellWord::endianness),
+        (UrlDecode::identify, "Percent encoding", UrlDecode::decode, UrlDecode::endianness),
+        (UrlComponents::identify, "URL", UrlComponents::decode, UrlComponents::endianness),
+        (Punycode::identify, "Punycode", Punycode::decode, Punycode::endianness),
+        (Idna::identify, "IDNA domain", Idna::decode, Idna::endianness),
         (UnicodeNames::identify, "Unicode character names", UnicodeNames::decode, UnicodeNames::endianness),
+        (CodePoints::identify, "Code point notation", CodePoints::decode, CodePoints::endianness),
         (common::Colour::identify, "HTML colour", common::Colour::decode, common::Colour::endianness),
     ];
 
@@ -63,23 +169,46 @@ fn main() {
         (FixedFloat::variants, "Floats", FixedFloat::encode, FixedFloat::endianness),
         (UUID::variants, "UUID", UUID::encode, UUID::endianness),
         (FixedInt::variants, "Primitive integers", FixedInt::encode, FixedInt::endianness),
-        (Base2_16::variants, "Base 2-16 numbers", Base2_16::encode, Base2_16::endianness),
+        (Base2_16::variants, "Base 2-36 numbers", Base2_16::encode, Base2_16::endianness),
         (Base64::variants, "Base64 data", Base64::encode, Base64::endianness),
         (Base85::variants, "Base85 data", Base85::encode, Base85::endianness),
         (Base91::variants, "Base91 data", Base91::encode, Base91::endianness),
+        (Base58::variants, "Base58 data", Base58::encode, Base58::endianness),
+        (Base45::variants, "Base45 data", Base45::encode, Base45::endianness),
+        (Base62::variants, "Base62 data", Base62::encode, Base62::endianness),
+        (Attachment::variants, "Attachment encoding", Attachment::encode, Attachment::endianness),
+        (Mime::variants, "MIME encoding", Mime::encode, Mime::endianness),
+        (Pem::variants, "PEM armor", Pem::encode, Pem::endianness),
+        (DataUri::variants, "Data URI", DataUri::encode, DataUri::endianness),
         (ByteList::variants, "Byte list", ByteList::encode, ByteList::endianness),
         (Unicode8::variants, "Unicode 8 string", Unicode8::encode, Unicode8::endianness),
+        (Normalization::variants, "Unicode normalization", Normalization::encode, Normalization::endianness),
+        (Unicode16::variants, "Unicode 16 string", Unicode16::encode, Unicode16::endianness),
+        (Unicode32::variants, "Unicode 32 string", Unicode32::encode, Unicode32::endianness),
+        (Codepage::variants, "Codepage string", Codepage::encode, Codepage::endianness),
         (EscapedString::variants, "Escaped sequence", EscapedString::encode, EscapedString::endianness),
-        (UrlEncode::variants, "Encoded URL", UrlEncode::encode, UrlEncode::endianness),
-        (UrlDecode::variants, "Decoded URL", UrlDecode::encode, UrlDecode::endianness),
+        (HtmlEntities::variants, "HTML entities", HtmlEntities::encode, HtmlEntities::endianness),
+        (RegexLiteral::variants, "Regex literal", RegexLiteral::encode, RegexLiteral::endianness),
+        (SqlString::variants, "SQL string", SqlString::encode, SqlString::endianness),
+        (ShellWord::variants, "Shell word", ShellWord::encode, ShellWord::endianness),
+        (UrlEncode::variants, "Percent encoding", UrlEncode::encode, UrlEncode::endianness),
+        (UrlComponents::variants, "URL", UrlComponents::encode, UrlComponents::endianness),
+        (Punycode::variants, "Punycode", Punycode::encode, Punycode::endianness),
+        (Idna::variants, "IDNA domain", Idna::encode, Idna::endianness),
         (common::Colour::variants, "Colour", common::Colour::encode, common::Colour::endianness),
     ];
 
 
 
+    //Outputs that keep details of an input in the same format, such as the label of a PEM document, which are read from the input's variant
+    let keeps_input: Vec<(& str, InputVariants)> = vec![
+        ("PEM armor", Pem::variants_for_input),
+        ("Data URI", DataUri::variants_for_input),
+    ];
+
     println!("Please enter the input string:");
 
-    let input = read_without_newline();
+    let input = read_input();
 
     let mut option_map = Vec::new();
 
@@ -89,7 +218,12 @@ fn main() {
 
     for (identifier, name, decoder, endianness) in to_ir {
 
-        let optional_variants = (identifier)(&input);
+        let mut optional_variants = (identifier)(&input);
+
+        //The custom alphabet is only known at runtime, so it's added after the bases from `Base2_16`
+        if let Some(variant) = alphabet.as_ref().filter(|_| name == "Base 2-36 number").and_then(|alphabet| Base2_16::identify_custom(&input, alphabet)) {
+            optional_variants.get_or_insert_with(Vec::new).push(variant);
+        }
 
         if let Some(variants) = optional_variants {
             println!("{}", name);
@@ -98,7 +232,7 @@ fn main() {
                 blue!("    {}", option_map.len());
                 println!("     {}", variant.0);
 
-                option_map.push((variant, decoder, endianness()));
+                option_map.push((name, variant, decoder, endianness()));
             }
         }
     }
@@ -114,25 +248,23 @@ fn main() {
                 panic!("Invalid number (must be between 0 and {}, inclusive)", option_map.len()-1);
             }
 
-            let (variant, decoder, endianness) = option_map[option].clone();
+            let (input_name, variant, decoder, endianness) = option_map[option].clone();
 
-            let (little_endian_ir, big_endian_ir) = {
+            let ir = {
 
 
-                let ir = (decoder)(&input, variant);
+                let ir = (decoder)(&input, variant.clone());
 
-                let reverse: Vec<_> = ir.iter().rev().map(|x| *x).collect();
-
-                if let Endianness::Dual = endianness {
+                if endianness.is_dual() {
                     println!("Would you like the result to be interpreted as little (l) or big (b) endian? If you're not sure, choose the default, little");
                     let user_endianness = read_without_newline();
 
                     match user_endianness.to_lowercase().as_str() {
                         "l" | "little" | "" => {
-                            (ir, reverse)
+                            ir
                         }
                         "b" | "big" => {
-                            (reverse, ir)
+                            endianness.swap(&ir)
                         }
                         _ => {
                             println!("Invalid endianness ('l' or 'little' for little endianness and 'b' or 'big' for big endianness");
@@ -140,7 +272,7 @@ fn main() {
                         }
                     }
                 } else {
-                    (ir, reverse)
+                    ir
                 }
             };
 
@@ -153,7 +285,14 @@ fn main() {
 
                 let endianness = endianness();
 
-                let optional_variants = (variants_function)(&little_endian_ir);
+                let mut optional_variants = match keeps_input.iter().find(|(format, _)| *format == name && *format == input_name) {
+                    Some((_, variants_for_input)) => (variants_for_input)(&ir, variant.clone()),
+                    None => (variants_function)(&ir),
+                };
+
+                if let Some(alphabet) = alphabet.as_ref().filter(|_| name == "Base 2-36 numbers") {
+                    optional_variants.get_or_insert_with(Vec::new).push(Base2_16::custom_variant(alphabet));
+                }
 
                 if let Some(variants) = optional_variants {
                     green!("{}", name);
@@ -164,13 +303,13 @@ fn main() {
                         yellow!("    {}", variant.0);
 
                         match endianness {
-                            Endianness::Default => {
-                                println!("    {}", (encoder)(&little_endian_ir, variant.clone()));
+                            Endianness::Default | Endianness::DualUnits(_) => {
+                                println!("    {}", (encoder)(&ir, variant.clone()));
                             },
                             Endianness::Dual => {
-                                print!("    {} ", (encoder)(&little_endian_ir, variant.clone()));
+                                print!("    {} ", (encoder)(&ir, variant.clone()));
                                 magenta!("(");
-                                print!("{}", (encoder)(&big_endian_ir, variant.clone()));
+                                print!("{}", (encoder)(&endianness.swap(&ir), variant.clone()));
                                 magenta!(")");
                                 println!();
                             }
@@ -0,0 +1,350 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use encoding_rs::Encoding;
+
+/// mime.rs implements quoted-printable (RFC 2045) and encoded-words (RFC 2047) used in email bodies and headers
+
+#[derive(Debug, Clone)]
+pub enum Variant {
+    QuotedPrintable, //Email bodies, with soft line breaks and lines of at most 76 characters
+    EncodedWord, //Header encoded-words, each decoded with its own encoding, so headers can mix B and Q words
+    EncodedWordB, //Header encoded-words using base64
+    EncodedWordQ, //Header encoded-words using the Q encoding, a restricted form of quoted-printable
+}
+
+//Maximum number of characters per line in quoted-printable, including the '=' of a soft line break
+const LINE_LENGTH: usize = 76;
+
+//Maximum number of characters in a single encoded-word, including the delimiters
+const ENCODED_WORD_LENGTH: usize = 75;
+
+//Charset used when encoding encoded-words
+const CHARSET: &str = "UTF-8";
+
+//Shortest line that is treated as wrapped when looking for soft line breaks
+const MINIMUM_WRAPPED_LENGTH: usize = 70;
+
+///Returns true if the line is a wrapped line of quoted-printable ending with a soft line break. Shorter lines, lines of padded base64 and
+///encoded-words also end with '=', so they are excluded
+pub fn is_soft_line_break(line: &str) -> bool {
+    let line = line.trim_end();
+
+    let is_base64 = line.len().is_multiple_of(4) && line.bytes().all(|x| x.is_ascii_alphanumeric() || x == b'+' || x == b'/' || x == b'=');
+
+    (MINIMUM_WRAPPED_LENGTH..=LINE_LENGTH).contains(&line.len())
+        && line.ends_with('=')
+        && !line.ends_with("?=")
+        && !is_base64
+        && MimeEncoding::decode_quoted_printable(line).is_ok()
+}
+
+pub struct MimeEncoding;
+
+impl MimeEncoding {
+    ///Decode the string. Text in encoded-words is converted from the named charset to UTF-8
+    pub fn decode(string: &str, variant: Variant) -> Result<Vec<u8>, ()> {
+        match variant {
+            Variant::QuotedPrintable => Self::decode_quoted_printable(string),
+            //The encoding is given by each word, so the B and Q variants decode the same way
+            Variant::EncodedWord | Variant::EncodedWordB | Variant::EncodedWordQ => Self::decode_header(string),
+        }
+    }
+
+    ///Encode the bytes. NOTE: encoded-words are labelled as UTF-8 and will panic if the bytes are not valid UTF-8
+    pub fn encode(bytes: & [u8], variant: Variant) -> String {
+        match variant {
+            Variant::QuotedPrintable => Self::encode_quoted_printable(bytes),
+            Variant::EncodedWordB => Self::encode_header(bytes, 'B'),
+            Variant::EncodedWordQ => Self::encode_header(bytes, 'Q'),
+            Variant::EncodedWord => panic!("Encoded-words are encoded with the B or Q variant"),
+        }
+    }
+
+    fn decode_hex(high: u8, low: u8) -> Result<u8, ()> {
+        let digit = |x: u8| (x as char).to_digit(16).ok_or(());
+
+        Ok((digit(high)? * 16 + digit(low)?) as u8)
+    }
+
+    fn decode_quoted_printable(string: &str) -> Result<Vec<u8>, ()> {
+        let mut bytes = Vec::new();
+        let lines = string.split('\n').collect::<Vec<_>>();
+
+        for (index, line) in lines.iter().enumerate() {
+            //Trailing whitespace may be added in transport, so it is ignored
+            let line = line.trim_end_matches(['\r', ' ', '\t']);
+
+            let (line, soft) = match line.strip_suffix('=') {
+                Some(line) => (line, true),
+                None => (line, false),
+            };
+
+            let mut characters = line.bytes();
+
+            while let Some(character) = characters.next() {
+                match character {
+                    b'=' => {
+                        let high = characters.next().ok_or(())?;
+                        let low = characters.next().ok_or(())?;
+
+                        bytes.push(Self::decode_hex(high, low)?);
+                    },
+                    b' ' | b'\t' | b'!'..=b'~' => bytes.push(character),
+                    _ => return Err(()),
+                }
+            }
+
+            //A hard line break represents the canonical CRLF
+            if !soft && index + 1 < lines.len() {
+                bytes.extend_from_slice(b"\r\n");
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    fn encode_quoted_printable(bytes: & [u8]) -> String {
+        let mut string = String::new();
+        let mut line = String::new();
+        let mut index = 0;
+
+        while index < bytes.len() {
+            if bytes[index..].starts_with(b"\r\n") {
+                string.push_str(&line);
+                string.push('\n');
+                line.clear();
+                index += 2;
+                continue;
+            }
+
+            let at_line_end = index + 1 == bytes.len() || bytes[index + 1..].starts_with(b"\r\n");
+
+            //Whitespace at the end of a line would be removed in transport, so it is escaped
+            let token = match bytes[index] {
+                b' ' | b'\t' if !at_line_end => (bytes[index] as char).to_string(),
+                b'!'..=b'<' | b'>'..=b'~' => (bytes[index] as char).to_string(),
+                byte => format!("={:02X}", byte),
+            };
+
+            //Leave room for the '=' of the soft line break
+            if line.len() + token.len() > LINE_LENGTH - 1 {
+                string.push_str(&line);
+                string.push_str("=\n");
+                line.clear();
+            }
+
+            line.push_str(&token);
+            index += 1;
+        }
+
+        string.push_str(&line);
+
+        string
+    }
+
+    fn decode_q(text: &str) -> Result<Vec<u8>, ()> {
+        let mut bytes = Vec::new();
+        let mut characters = text.bytes();
+
+        while let Some(character) = characters.next() {
+            match character {
+                b'_' => bytes.push(b' '),
+                b'=' => {
+                    let high = characters.next().ok_or(())?;
+                    let low = characters.next().ok_or(())?;
+
+                    bytes.push(Self::decode_hex(high, low)?);
+                },
+                b'!'..=b'~' if character != b'?' => bytes.push(character),
+                _ => return Err(()),
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    ///Q encoding, only leaving characters that are allowed everywhere in a header unescaped
+    fn encode_q(bytes: & [u8]) -> String {
+        bytes.iter().map(|&byte| match byte {
+            b' ' => String::from("_"),
+            b'0'..=b'9' | b'A'..=b'Z' | b'a'..=b'z' | b'!' | b'*' | b'+' | b'-' | b'/' => (byte as char).to_string(),
+            _ => format!("={:02X}", byte),
+        }).collect()
+    }
+
+    fn encode_text(bytes: & [u8], encoding: char) -> String {
+        match encoding {
+            'B' => base64::encode(bytes),
+            _ => Self::encode_q(bytes),
+        }
+    }
+
+    ///Decode a header containing encoded-words, each using the encoding ('B' or 'Q') named in the word. Text outside the encoded-words is kept
+    fn decode_header(string: &str) -> Result<Vec<u8>, ()> {
+        lazy_static! {
+            static ref ENCODED_WORD: Regex = Regex::new("=\\?([^?\\s]+)\\?([BbQq])\\?([^?\\s]*)\\?=").unwrap();
+        }
+
+        let mut bytes = Vec::new();
+        let mut last_end = None;
+
+        for captures in ENCODED_WORD.captures_iter(string) {
+            let word = captures.get(0).unwrap();
+            let between = &string[last_end.unwrap_or(0)..word.start()];
+
+            //Whitespace between adjacent encoded-words is not part of the text
+            if last_end.is_none() || !between.trim().is_empty() {
+                bytes.extend_from_slice(between.as_bytes());
+            }
+
+            last_end = Some(word.end());
+
+            let text = captures.get(3).unwrap().as_str();
+
+            let raw = match captures.get(2).unwrap().as_str() {
+                "B" | "b" => base64::decode(text).map_err(|_| ())?,
+                _ => Self::decode_q(text)?,
+            };
+
+            //RFC 2231 allows a language to follow the charset, such as UTF-8*en
+            let charset = captures.get(1).unwrap().as_str().split('*').next().unwrap();
+
+            let text = Encoding::for_label(charset.as_bytes())
+                .ok_or(())?
+                .decode_without_bom_handling_and_without_replacement(&raw)
+                .ok_or(())?;
+
+            bytes.extend_from_slice(text.as_bytes());
+        }
+
+        match last_end {
+            Some(end) => {
+                bytes.extend_from_slice(&string.as_bytes()[end..]);
+
+                Ok(bytes)
+            },
+            None => Err(()),
+        }
+    }
+
+    ///Encode the bytes as encoded-words separated by spaces, splitting on character boundaries to keep each word within 75 characters
+    fn encode_header(bytes: & [u8], encoding: char) -> String {
+        let text = std::str::from_utf8(bytes).unwrap();
+
+        let prefix = format!("=?{}?{}?", CHARSET, encoding);
+        let available = ENCODED_WORD_LENGTH - prefix.len() - 2;
+
+        let mut words = Vec::new();
+        let mut start = 0;
+        let mut end = 0;
+
+        for boundary in text.char_indices().map(|(index, _)| index).skip(1).chain(std::iter::once(text.len())) {
+            if end > start && Self::encode_text(&bytes[start..boundary], encoding).len() > available {
+                words.push(&bytes[start..end]);
+                start = end;
+            }
+
+            end = boundary;
+        }
+
+        if end > start || words.is_empty() {
+            words.push(&bytes[start..end]);
+        }
+
+        words.iter()
+            .map(|word| format!("{}{}?=", prefix, Self::encode_text(word, encoding)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_printable_soft_line_breaks() {
+        assert_eq!(MimeEncoding::decode("soft=\nbreak", Variant::QuotedPrintable), Ok(b"softbreak".to_vec()));
+        assert_eq!(MimeEncoding::decode("hard\nbreak", Variant::QuotedPrintable), Ok(b"hard\r\nbreak".to_vec()));
+        assert_eq!(MimeEncoding::decode("soft= \r\nbreak\r\n", Variant::QuotedPrintable), Ok(b"softbreak\r\n".to_vec()));
+        assert_eq!(MimeEncoding::decode("caf=C3=A9 =3D x", Variant::QuotedPrintable), Ok("café = x".as_bytes().to_vec()));
+
+        assert!(MimeEncoding::decode("=4", Variant::QuotedPrintable).is_err());
+        assert!(MimeEncoding::decode("=XY", Variant::QuotedPrintable).is_err());
+    }
+
+    #[test]
+    fn quoted_printable_wrapping() {
+        let encoded = MimeEncoding::encode("hello world ".repeat(8).trim_end().as_bytes(), Variant::QuotedPrintable);
+        let lines: Vec<_> = encoded.split('\n').collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), LINE_LENGTH);
+        assert!(lines[0].ends_with('='));
+        assert!(is_soft_line_break(lines[0]));
+        assert!(!is_soft_line_break(lines[1]));
+
+        //Whitespace at the end of a line is escaped
+        assert_eq!(MimeEncoding::encode(b"a \r\nb\t", Variant::QuotedPrintable), "a=20\nb=09");
+    }
+
+    #[test]
+    fn soft_line_break_excludes_other_formats() {
+        //Padded base64, an encoded-word and a short line all end with '='
+        assert!(!is_soft_line_break(&format!("{}==", "QUJD".repeat(18))));
+        assert!(!is_soft_line_break(&format!("=?UTF-8?Q?{}?=", "a".repeat(60))));
+        assert!(!is_soft_line_break("short="));
+    }
+
+    #[test]
+    fn encoded_word_known_vectors() {
+        //Examples from RFC 2047
+        assert_eq!(MimeEncoding::decode("=?US-ASCII?Q?Keith_Moore?= <moore@cs.utk.edu>", Variant::EncodedWord), Ok(b"Keith Moore <moore@cs.utk.edu>".to_vec()));
+        assert_eq!(MimeEncoding::decode("=?ISO-8859-1?Q?Andr=E9?= Pirard", Variant::EncodedWord), Ok("André Pirard".as_bytes().to_vec()));
+        assert_eq!(MimeEncoding::decode("(=?ISO-8859-1?Q?a?= =?ISO-8859-2?Q?_b?=)", Variant::EncodedWord), Ok(b"(a b)".to_vec()));
+        assert_eq!(MimeEncoding::decode("=?ISO-8859-1?B?SWYgeW91IGNhbiByZWFkIHRoaXMgeW8=?=", Variant::EncodedWord), Ok(b"If you can read this yo".to_vec()));
+
+        //RFC 2231 language
+        assert_eq!(MimeEncoding::decode("=?US-ASCII*EN?Q?Keith_Moore?=", Variant::EncodedWord), Ok(b"Keith Moore".to_vec()));
+    }
+
+    #[test]
+    fn encoded_word_mixed_encodings() {
+        //Each word is decoded with its own encoding
+        let header = "=?UTF-8?B?R3LDvA==?= =?UTF-8?Q?=C3=9Fe?= and =?iso-8859-1?q?caf=E9?=";
+
+        assert_eq!(MimeEncoding::decode(header, Variant::EncodedWord), Ok("Grüße and café".as_bytes().to_vec()));
+        assert_eq!(MimeEncoding::decode(header, Variant::EncodedWordB), MimeEncoding::decode(header, Variant::EncodedWord));
+
+        //A B word that isn't base64 is still rejected
+        assert!(MimeEncoding::decode("=?UTF-8?Q?a?= =?UTF-8?B?a=b?=", Variant::EncodedWord).is_err());
+        assert!(MimeEncoding::decode("no encoded-words", Variant::EncodedWord).is_err());
+    }
+
+    #[test]
+    fn encoded_word_splitting() {
+        let text = "é".repeat(40);
+
+        for variant in [Variant::EncodedWordB, Variant::EncodedWordQ] {
+            let encoded = MimeEncoding::encode(text.as_bytes(), variant.clone());
+
+            assert!(encoded.split(' ').all(|word| word.len() <= ENCODED_WORD_LENGTH && word.starts_with("=?UTF-8?")));
+            assert_eq!(MimeEncoding::decode(&encoded, Variant::EncodedWord), Ok(text.as_bytes().to_vec()));
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..=255).chain(b"line\r\nline \r\n".iter().copied()).collect();
+
+        let encoded = MimeEncoding::encode(&bytes, Variant::QuotedPrintable);
+
+        assert!(encoded.lines().all(|line| line.len() <= LINE_LENGTH));
+        assert_eq!(MimeEncoding::decode(&encoded, Variant::QuotedPrintable), Ok(bytes));
+
+        let text = "Grüße, \"world\" = 1 + 2?";
+
+        assert_eq!(MimeEncoding::decode(&MimeEncoding::encode(text.as_bytes(), Variant::EncodedWordQ), Variant::EncodedWord), Ok(text.as_bytes().to_vec()));
+        assert_eq!(MimeEncoding::decode(&MimeEncoding::encode(text.as_bytes(), Variant::EncodedWordB), Variant::EncodedWord), Ok(text.as_bytes().to_vec()));
+    }
+}
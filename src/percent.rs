@@ -0,0 +1,154 @@
+use percent_encoding::{AsciiSet, CONTROLS, NON_ALPHANUMERIC, percent_encode, percent_decode_str};
+
+/// percent.rs implements percent-encoding with the WHATWG URL encode sets, RFC 3986 and form encoding, and the detection of
+/// percent-encoded text
+
+#[derive(Debug, Clone)]
+pub enum EncodeSet {
+    Minimal, //Only what can't appear in a URL as it is: control characters, space, '%' and non-ASCII
+    Query, //WHATWG query percent-encode set
+    Path, //WHATWG path percent-encode set
+    Userinfo, //WHATWG userinfo percent-encode set
+    Component, //WHATWG component percent-encode set, as used by encodeURIComponent
+    Form, //application/x-www-form-urlencoded, with spaces as '+'
+    Reserved, //Everything except RFC 3986 reserved and unreserved characters, as used by encodeURI
+    Unreserved, //Everything except RFC 3986 unreserved characters
+}
+
+const MINIMAL: &AsciiSet = &CONTROLS.add(b' ').add(b'%');
+const QUERY: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'<').add(b'>');
+const PATH: &AsciiSet = &QUERY.add(b'?').add(b'`').add(b'{').add(b'}');
+const USERINFO: &AsciiSet = &PATH.add(b'/').add(b':').add(b';').add(b'=').add(b'@').add(b'[').add(b'\\').add(b']').add(b'^').add(b'|');
+const COMPONENT: &AsciiSet = &USERINFO.add(b'$').add(b'%').add(b'&').add(b'+').add(b',');
+const FORM: &AsciiSet = &COMPONENT.add(b'!').add(b'\'').add(b'(').add(b')').add(b'~');
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+const RESERVED: &AsciiSet = &UNRESERVED.remove(b':').remove(b'/').remove(b'?').remove(b'#').remove(b'[').remove(b']').remove(b'@')
+    .remove(b'!').remove(b'$').remove(b'&').remove(b'\'').remove(b'(').remove(b')').remove(b'*').remove(b'+').remove(b',').remove(b';').remove(b'=');
+
+impl EncodeSet {
+    pub const ALL: [EncodeSet; 8] = [EncodeSet::Minimal, EncodeSet::Query, EncodeSet::Path, EncodeSet::Userinfo, EncodeSet::Component, EncodeSet::Form, EncodeSet::Reserved, EncodeSet::Unreserved];
+
+    pub fn name(&self) -> & 'static str {
+        match self {
+            EncodeSet::Minimal => "Minimal",
+            EncodeSet::Query => "Query",
+            EncodeSet::Path => "Path",
+            EncodeSet::Userinfo => "Userinfo",
+            EncodeSet::Component => "Component",
+            EncodeSet::Form => "Form",
+            EncodeSet::Reserved => "RFC 3986 reserved",
+            EncodeSet::Unreserved => "RFC 3986 unreserved",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|set| set.name() == name).cloned()
+    }
+
+    fn ascii_set(&self) -> & 'static AsciiSet {
+        match self {
+            EncodeSet::Minimal => MINIMAL,
+            EncodeSet::Query => QUERY,
+            EncodeSet::Path => PATH,
+            EncodeSet::Userinfo => USERINFO,
+            EncodeSet::Component => COMPONENT,
+            EncodeSet::Form => FORM,
+            EncodeSet::Reserved => RESERVED,
+            EncodeSet::Unreserved => UNRESERVED,
+        }
+    }
+}
+
+pub struct PercentEncoding;
+
+impl PercentEncoding {
+    ///Percent-encode the bytes in the set. Bytes that are not ASCII are always encoded
+    pub fn encode(bytes: &[u8], set: EncodeSet) -> String {
+        let encoded = percent_encode(bytes, set.ascii_set()).to_string();
+
+        //The form set encodes '+', so any "%20" is a space
+        match set {
+            EncodeSet::Form => encoded.replace("%20", "+"),
+            _ => encoded,
+        }
+    }
+
+    ///Decode percent-encoded text, where form encoding also has '+' for a space
+    pub fn decode(text: &str, form: bool) -> Vec<u8> {
+        if form {
+            percent_decode_str(&text.replace('+', " ")).collect()
+        } else {
+            percent_decode_str(text).collect()
+        }
+    }
+
+    ///Returns true if the text has at least one percent-encoded byte, and every '%' starts one
+    pub fn is_encoded(text: &str) -> bool {
+        let mut parts = text.split('%');
+
+        parts.next();
+
+        let mut count = 0;
+
+        for part in parts {
+            if part.len() < 2 || !part.as_bytes()[..2].iter().all(|x| x.is_ascii_hexdigit()) {
+                return false;
+            }
+
+            count += 1;
+        }
+
+        count > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_sets() {
+        let text = "a b/c?d=e&f#g~'\u{E9}%";
+
+        let encoded: Vec<_> = EncodeSet::ALL.iter().map(|set| PercentEncoding::encode(text.as_bytes(), set.clone())).collect();
+
+        assert_eq!(encoded, vec![
+            "a%20b/c?d=e&f#g~'%C3%A9%25",
+            "a%20b/c?d=e&f%23g~'%C3%A9%",
+            "a%20b/c%3Fd=e&f%23g~'%C3%A9%",
+            "a%20b%2Fc%3Fd%3De&f%23g~'%C3%A9%",
+            "a%20b%2Fc%3Fd%3De%26f%23g~'%C3%A9%25",
+            "a+b%2Fc%3Fd%3De%26f%23g%7E%27%C3%A9%25",
+            "a%20b/c?d=e&f#g~'%C3%A9%25",
+            "a%20b%2Fc%3Fd%3De%26f%23g~%27%C3%A9%25",
+        ]);
+    }
+
+    #[test]
+    fn decode_form() {
+        assert_eq!(PercentEncoding::decode("a+b%2Bc%20d", false), b"a+b+c d");
+        assert_eq!(PercentEncoding::decode("a+b%2Bc%20d", true), b"a b+c d");
+
+        //Invalid escapes are left as they are
+        assert_eq!(PercentEncoding::decode("100%", false), b"100%");
+    }
+
+    #[test]
+    fn detect_encoded() {
+        assert!(PercentEncoding::is_encoded("caf%C3%A9"));
+        assert!(!PercentEncoding::is_encoded("no escapes"));
+        assert!(!PercentEncoding::is_encoded("100% sure"));
+        assert!(!PercentEncoding::is_encoded("%41 and 5%"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+
+        for set in EncodeSet::ALL.iter() {
+            let form = matches!(set, EncodeSet::Form);
+
+            assert_eq!(PercentEncoding::decode(&PercentEncoding::encode(&bytes, set.clone()), form), bytes, "{}", set.name());
+        }
+    }
+}
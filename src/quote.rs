@@ -0,0 +1,351 @@
+use std::str::from_utf8;
+
+/// quote.rs implements quoting text as a literal in regexes, SQL and the POSIX shell, and unquoting those literals again
+
+#[derive(Debug, Clone)]
+pub enum Flavour {
+    Pcre, //Any non-alphanumeric character can be escaped, and \Q..\E quotes a whole run
+    PosixEre, //Only the special characters can be escaped
+    Rust, //The regex crate, which also escapes # & - ~ for verbose mode and classes
+}
+
+#[derive(Debug, Clone)]
+pub enum Dialect {
+    Ansi, //Quotes are doubled, there are no other escapes
+    MySql, //Backslash escapes, as well as doubled quotes
+    Hex, //X'..' with two hex digits per byte
+}
+
+//Characters with a meaning outside brackets in every flavour. Unescaped, they mean the regex is not a literal
+const SPECIAL: &str = "\\^$.|?*+()[{";
+
+//Characters that can't appear unquoted in a shell word. Carriage returns aren't shell syntax, but are rejected so they can't hide the rest
+//of a line when it's displayed
+const SHELL_SPECIAL: &str = " \t\r\n|&;<>()$`*?[";
+
+//Characters that can't start an unquoted shell word, for tilde expansion and comments
+const SHELL_WORD_START: [char; 2] = ['~', '#'];
+
+impl Flavour {
+    pub const ALL: [Flavour; 3] = [Flavour::Pcre, Flavour::PosixEre, Flavour::Rust];
+
+    pub fn name(&self) -> & 'static str {
+        match self {
+            Flavour::Pcre => "PCRE",
+            Flavour::PosixEre => "POSIX ERE",
+            Flavour::Rust => "Rust regex",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|flavour| flavour.name() == name).cloned()
+    }
+}
+
+impl Dialect {
+    pub const ALL: [Dialect; 3] = [Dialect::Ansi, Dialect::MySql, Dialect::Hex];
+
+    pub fn name(&self) -> & 'static str {
+        match self {
+            Dialect::Ansi => "ANSI",
+            Dialect::MySql => "MySQL",
+            Dialect::Hex => "Hex",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().find(|dialect| dialect.name() == name).cloned()
+    }
+}
+
+pub struct Quoting;
+
+impl Quoting {
+    ///Escape the text so that the regex matches it literally
+    pub fn escape_regex(text: &str, flavour: Flavour) -> String {
+        let special = match flavour {
+            Flavour::Pcre => "\\^$.|?*+()[]{}",
+            Flavour::PosixEre => SPECIAL,
+            Flavour::Rust => return regex::escape(text),
+        };
+
+        let mut escaped = String::new();
+
+        for character in text.chars() {
+            if special.contains(character) {
+                escaped.push('\\');
+            }
+
+            escaped.push(character);
+        }
+
+        escaped
+    }
+
+    ///The text matched by a regex that only matches literally, or an error if the regex has unescaped special characters or
+    ///escapes such as \d that match more than one character
+    pub fn unescape_regex(regex: &str, flavour: Flavour) -> Result<String, ()> {
+        let mut text = String::new();
+        let mut characters = regex.chars();
+
+        while let Some(character) = characters.next() {
+            if character != '\\' {
+                if SPECIAL.contains(character) {
+                    return Err(());
+                }
+
+                text.push(character);
+                continue;
+            }
+
+            let escaped = characters.next().ok_or(())?;
+
+            let unescaped = match (escaped, &flavour) {
+                _ if SPECIAL.contains(escaped) || escaped == ']' || escaped == '}' => escaped,
+                //Everything up to \E, or the end of the regex, is literal
+                ('Q', Flavour::Pcre) => {
+                    let rest = characters.as_str();
+                    let end = rest.find("\\E").unwrap_or(rest.len());
+
+                    text.push_str(&rest[..end]);
+                    characters = rest[(end + 2).min(rest.len())..].chars();
+                    continue;
+                },
+                ('E', Flavour::Pcre) => continue,
+                ('x', Flavour::Pcre | Flavour::Rust) => {
+                    let rest = characters.as_str();
+
+                    let (digits, length) = match rest.strip_prefix('{') {
+                        Some(braced) => {
+                            let end = braced.find('}').ok_or(())?;
+                            (&braced[..end], end + 2)
+                        },
+                        None => (rest.get(..2).ok_or(())?, 2),
+                    };
+
+                    if digits.is_empty() || !digits.chars().all(|x| x.is_ascii_hexdigit()) {
+                        return Err(());
+                    }
+
+                    characters = rest[length..].chars();
+
+                    u32::from_str_radix(digits, 16).ok().and_then(char::from_u32).ok_or(())?
+                },
+                ('n', Flavour::Pcre | Flavour::Rust) => '\n',
+                ('r', Flavour::Pcre | Flavour::Rust) => '\r',
+                ('t', Flavour::Pcre | Flavour::Rust) => '\t',
+                ('f', Flavour::Pcre | Flavour::Rust) => '\x0C',
+                ('a', Flavour::Pcre | Flavour::Rust) => '\x07',
+                ('e', Flavour::Pcre) => '\x1B',
+                ('v', Flavour::Rust) => '\x0B',
+                (_, Flavour::Pcre) if !escaped.is_alphanumeric() => escaped,
+                (_, Flavour::Rust) if escaped.is_ascii_punctuation() => escaped,
+                _ => return Err(()),
+            };
+
+            text.push(unescaped);
+        }
+
+        Ok(text)
+    }
+
+    ///Quote the bytes as a SQL string literal, or None if the dialect needs text
+    pub fn quote_sql(bytes: &[u8], dialect: Dialect) -> Option<String> {
+        let text = match dialect {
+            Dialect::Hex => return Some(format!("X'{}'", bytes.iter().map(|byte| format!("{:02X}", byte)).collect::<String>())),
+            _ => from_utf8(bytes).ok()?,
+        };
+
+        let quoted: String = match dialect {
+            Dialect::MySql => text.chars().map(|character| match character {
+                '\0' => String::from("\\0"),
+                '\'' => String::from("\\'"),
+                '"' => String::from("\\\""),
+                '\n' => String::from("\\n"),
+                '\r' => String::from("\\r"),
+                '\\' => String::from("\\\\"),
+                '\x1A' => String::from("\\Z"),
+                _ => character.to_string(),
+            }).collect(),
+            _ => text.replace('\'', "''"),
+        };
+
+        Some(format!("'{}'", quoted))
+    }
+
+    pub fn unquote_sql(literal: &str, dialect: Dialect) -> Result<Vec<u8>, ()> {
+        if let Dialect::Hex = dialect {
+            let digits = literal.strip_prefix(['X', 'x']).and_then(|x| x.strip_prefix('\'')).and_then(|x| x.strip_suffix('\'')).ok_or(())?;
+
+            if digits.len() % 2 != 0 || !digits.bytes().all(|x| x.is_ascii_hexdigit()) {
+                return Err(());
+            }
+
+            return Ok((0..digits.len()).step_by(2).map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap()).collect());
+        }
+
+        let body = literal.strip_prefix('\'').and_then(|x| x.strip_suffix('\'')).ok_or(())?;
+
+        let mut text = String::new();
+        let mut characters = body.chars();
+
+        while let Some(character) = characters.next() {
+            match (character, &dialect) {
+                //A quote inside the literal has to be doubled
+                ('\'', _) => {
+                    if characters.next() != Some('\'') {
+                        return Err(());
+                    }
+
+                    text.push('\'');
+                },
+                ('\\', Dialect::MySql) => match characters.next().ok_or(())? {
+                    '0' => text.push('\0'),
+                    'b' => text.push('\x08'),
+                    'n' => text.push('\n'),
+                    'r' => text.push('\r'),
+                    't' => text.push('\t'),
+                    'Z' => text.push('\x1A'),
+                    //\% and \_ keep their backslash, so they can be used in LIKE patterns
+                    escaped @ ('%' | '_') => {
+                        text.push('\\');
+                        text.push(escaped);
+                    },
+                    escaped => text.push(escaped),
+                },
+                _ => text.push(character),
+            }
+        }
+
+        Ok(text.into_bytes())
+    }
+
+    ///Quote the text as a single word for a POSIX shell, in single quotes. Single quotes in the text end the quoted part, and are
+    ///written as \' between quoted parts
+    pub fn quote_shell(text: &str) -> String {
+        format!("'{}'", text.replace('\'', "'\\''"))
+    }
+
+    ///The text of a shell word made of single quoted, double quoted, backslash escaped and plain parts, or an error if it has
+    ///expansions or more than one word
+    pub fn unquote_shell(word: &str) -> Result<String, ()> {
+        if word.starts_with(SHELL_WORD_START) {
+            return Err(());
+        }
+
+        let mut text = String::new();
+        let mut characters = word.chars();
+
+        while let Some(character) = characters.next() {
+            match character {
+                '\'' => loop {
+                    match characters.next().ok_or(())? {
+                        '\'' => break,
+                        quoted => text.push(quoted),
+                    }
+                },
+                '"' => loop {
+                    match characters.next().ok_or(())? {
+                        '"' => break,
+                        '$' | '`' => return Err(()),
+                        //Inside double quotes a backslash only escapes $ ` " \ and line breaks
+                        '\\' => match characters.next().ok_or(())? {
+                            '\n' => (),
+                            escaped @ ('$' | '`' | '"' | '\\') => text.push(escaped),
+                            escaped => {
+                                text.push('\\');
+                                text.push(escaped);
+                            },
+                        },
+                        quoted => text.push(quoted),
+                    }
+                },
+                '\\' => match characters.next().ok_or(())? {
+                    '\n' => (),
+                    escaped => text.push(escaped),
+                },
+                _ if SHELL_SPECIAL.contains(character) => return Err(()),
+                _ => text.push(character),
+            }
+        }
+
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regex_escapes() {
+        assert_eq!(Quoting::escape_regex("a.b*(c)", Flavour::Pcre), "a\\.b\\*\\(c\\)");
+        assert_eq!(Quoting::escape_regex("1+1=2?", Flavour::PosixEre), "1\\+1=2\\?");
+        assert_eq!(Quoting::escape_regex("a-b#c", Flavour::Rust), "a\\-b\\#c");
+    }
+
+    #[test]
+    fn regex_literals() {
+        assert_eq!(Quoting::unescape_regex("a\\.b\\Q.*\\Ec", Flavour::Pcre), Ok(String::from("a.b.*c")));
+        assert_eq!(Quoting::unescape_regex("\\x41\\x{263a}\\t", Flavour::Rust), Ok(String::from("A\u{263A}\t")));
+        assert_eq!(Quoting::unescape_regex("a\\+b", Flavour::PosixEre), Ok(String::from("a+b")));
+
+        //Unescaped special characters and escapes that match more than one character
+        assert!(Quoting::unescape_regex("a.b", Flavour::Pcre).is_err());
+        assert!(Quoting::unescape_regex("\\d+", Flavour::Pcre).is_err());
+        assert!(Quoting::unescape_regex("\\n", Flavour::PosixEre).is_err());
+        assert!(Quoting::unescape_regex("\\x{110000}", Flavour::Rust).is_err());
+    }
+
+    #[test]
+    fn sql_literals() {
+        assert_eq!(Quoting::quote_sql(b"it's", Dialect::Ansi), Some(String::from("'it''s'")));
+        assert_eq!(Quoting::quote_sql(b"it's\n\\", Dialect::MySql), Some(String::from("'it\\'s\\n\\\\'")));
+        assert_eq!(Quoting::quote_sql(&[0xDE, 0xAD], Dialect::Hex), Some(String::from("X'DEAD'")));
+        assert_eq!(Quoting::quote_sql(&[0xFF], Dialect::Ansi), None);
+
+        assert_eq!(Quoting::unquote_sql("'it''s'", Dialect::Ansi), Ok(b"it's".to_vec()));
+        assert_eq!(Quoting::unquote_sql("'a\\Z\\%'", Dialect::MySql), Ok(b"a\x1A\\%".to_vec()));
+        assert_eq!(Quoting::unquote_sql("x'dead'", Dialect::Hex), Ok(vec![0xDE, 0xAD]));
+
+        assert!(Quoting::unquote_sql("'it's'", Dialect::Ansi).is_err());
+        assert!(Quoting::unquote_sql("X'ABC'", Dialect::Hex).is_err());
+    }
+
+    #[test]
+    fn shell_words() {
+        assert_eq!(Quoting::quote_shell("it's $HOME"), "'it'\\''s $HOME'");
+        assert_eq!(Quoting::unquote_shell("'it'\\''s'\"\\$x\\n\"plain\\ word"), Ok(String::from("it's$x\\nplain word")));
+
+        //Expansions, more than one word and unterminated quotes
+        assert!(Quoting::unquote_shell("\"$HOME\"").is_err());
+        assert!(Quoting::unquote_shell("a;b").is_err());
+        assert!(Quoting::unquote_shell("two words").is_err());
+        assert!(Quoting::unquote_shell("'open").is_err());
+
+        //Tilde expansion and comments only start a word, and carriage returns are rejected unquoted
+        assert!(Quoting::unquote_shell("~").is_err());
+        assert!(Quoting::unquote_shell("~root/file").is_err());
+        assert!(Quoting::unquote_shell("#comment").is_err());
+        assert!(Quoting::unquote_shell("a\rb").is_err());
+        assert_eq!(Quoting::unquote_shell("a~b#c"), Ok(String::from("a~b#c")));
+        assert_eq!(Quoting::unquote_shell("'~'\\#"), Ok(String::from("~#")));
+        assert_eq!(Quoting::unquote_shell("\\~"), Ok(String::from("~")));
+        assert_eq!(Quoting::unquote_shell("'a\rb'"), Ok(String::from("a\rb")));
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "it's \"a\" (test) [1+2]*3? {x} ^$ | \\ \t\n\u{E9}";
+
+        for flavour in [Flavour::Pcre, Flavour::PosixEre, Flavour::Rust] {
+            assert_eq!(Quoting::unescape_regex(&Quoting::escape_regex(text, flavour.clone()), flavour), Ok(String::from(text)));
+        }
+
+        for dialect in [Dialect::Ansi, Dialect::MySql, Dialect::Hex] {
+            assert_eq!(Quoting::unquote_sql(&Quoting::quote_sql(text.as_bytes(), dialect.clone()).unwrap(), dialect), Ok(text.as_bytes().to_vec()));
+        }
+
+        assert_eq!(Quoting::unquote_shell(&Quoting::quote_shell(text)), Ok(String::from(text)));
+    }
+}
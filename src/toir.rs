@@ -4,6 +4,19 @@ use std::str::FromStr;
 use half::f16;
 use lazy_static::lazy_static;
 use regex::Regex;
+use crate::base85::Base85Encoding;
+use crate::compact::{Base45Encoding, Base62Encoding};
+use crate::attachment::AttachmentEncoding;
+use crate::mime::MimeEncoding;
+use crate::armor::{PemEncoding, DataUriEncoding, remove_line_breaks};
+use crate::utf::UtfEncoding;
+use crate::codepage::{CharacterSet, CODEPAGES};
+use crate::entity::EntityEncoding;
+use crate::quote::{Quoting, Flavour, Dialect};
+use crate::literal::{SourceString, Language};
+use crate::uri::UrlParser;
+use crate::percent::PercentEncoding;
+use crate::idn::{DomainName, Processing};
 
 //A trait that defines functions to convert from & str to IR
 pub trait ToIR {
@@ -15,7 +28,7 @@ pub trait ToIR {
     fn decode(value: & str, variant: Variant) -> Vec<u8>;
 }
 
-///Numbers from base 2 to base 16 using 0-9 and a-f
+///Numbers from base 2 to base 36 using 0-9 and a-z (case insensitive), or up to base 64 using a custom alphabet
 impl ToIR for crate::common::Base2_16 {
 
     fn identify(value: &str) -> Option<Vec<Variant>> {
@@ -26,23 +39,21 @@ impl ToIR for crate::common::Base2_16 {
             if character == '0' {
                 if let Some(character) = chars.next() {
                     match character {
-                        'b' => return Some(vec![Variant("Base 2")]),
-                        'o' => return Some(vec![Variant("Base 8")]),
-                        'x' => return Some(vec![Variant("Base 16")]),
+                        'b' => return Some(vec![Variant::new("Base 2"), Variant::new("Base 2 padded")]),
+                        'o' => return Some(vec![Variant::new("Base 8"), Variant::new("Base 8 padded")]),
+                        'x' => return Some(vec![Variant::new("Base 16"), Variant::new("Base 16 padded")]),
                         _ => {}
                     }
                 }
             }
         }
 
-        let is_valid_base16 = value.as_bytes().iter().fold(true, |acc, &x| {
-            acc && ((x >= '0' as u8 && x <= '9' as u8) || (x >= 'a' as u8 && x <= 'f' as u8) || (x >= 'A' as u8 && x <= 'F' as u8))
-        });
+        let is_valid_base36 = value.as_bytes().iter().all(u8::is_ascii_alphanumeric);
 
-        //If the number does not convert to Hexadecimal, it cannot be valid base 16 or lower
-        if !is_valid_base16 {
-            return None;
-        } else { //Get a list of all possible bases, based on the largest digit. (for example if the largest digit is 1, then it could be base 2 or more. If the largest digit is 7,, it could be base 8 or more)
+        //If the number only contains 0-9a-z, the smallest possible base is based on the largest digit. (for example if the largest digit is 1,
+        //then it could be base 2 or more. If the largest digit is 7, it could be base 8 or more). Every base up to 36 would be too many to
+        //list, so only the smallest base and the common bases above it are used, with bases that give the same bytes grouped together
+        if is_valid_base36 {
 
             let largest = value
                 .as_bytes()
@@ -51,47 +62,66 @@ impl ToIR for crate::common::Base2_16 {
                 .max()
                 .unwrap_or(0);
 
-            let possible_bases= [Variant("Base 2"), //Variant("Base 3"), Variant("Base 4"), Variant("Base 5"), Variant("Base 6"), Variant("Base 7"),
-                Variant("Base 8"), //Variant("Base 9"),
-                Variant("Base 10"), //Variant("Base 11"), Variant("Base 12"), Variant("Base 13"), Variant("Base 14"), Variant("Base 15"),
-                Variant("Base 16")
-            ];
-
-            let index = if largest < 2 {
-                    0
-                } else if largest >= 2 && largest < 8 {
-                    1
-                } else if largest >= 8 && largest < 10 {
-                    2
-                } else {
-                    3
-                }
-            ;
+            let smallest = std::cmp::max(largest as u64 + 1, 2);
 
-            let variants = Vec::from(&possible_bases[index..]);
+            let mut decoded = Vec::new();
 
-            Some(variants)
+            for base in (smallest..=36).filter(|base| *base == smallest || Self::COMMON_BASES.contains(base)) {
+                for name in std::iter::once(Self::base_name(base)).chain(Self::padded_name(base)) {
+                    decoded.push((name, Self::decode(value, Variant::new(name))));
+                }
+            }
 
+            Some(Variant::grouped(decoded))
+        } else {
+            None
         }
 
     }
 
     fn decode(mut value: &str, variant: Variant) -> Vec<u8> {
 
-        if value.len() > 1 {
-            if &value[0..2] == "0b" || &value[0..2] == "0o" || &value[0..2] == "0x" {
-                value = &value[2..];
+        let base = Self::get_base(&variant);
+
+        let input: Vec<_> = if Self::is_custom(&variant) {
+            let alphabet = Self::custom_alphabet(&variant);
+
+            //Convert characters into their position in the custom alphabet
+            value.chars()
+                .rev()
+                .map(|character| alphabet.iter().position(|&x| x == character).unwrap() as u8).collect()
+        } else {
+            if value.len() > 1 {
+                if &value[0..2] == "0b" || &value[0..2] == "0o" || &value[0..2] == "0x" {
+                    value = &value[2..];
+                }
             }
+
+            //Convert ascii bytes 0-9a-zA-Z into digits
+            value.as_bytes().iter()
+                .rev()
+                .map(|&byte| Self::ascii_to_num(byte)).collect()
+        };
+
+        let mut bytes = convert_base::Convert::new(base, 256).convert::<u8, u8>(& input);
+
+        //Remove leading zero bytes, keeping at least one byte
+        while bytes.len() > 1 && bytes.last() == Some(&0) {
+            bytes.pop();
         }
 
-        let base = Self::get_base(&variant);
+        //Padded variants keep the leading zero bytes, so each pair of hex digits (or 8 binary digits) is one byte, in the order they're written
+        if let Some(bits) = Self::bits_per_digit(base).filter(|_| Self::is_padded(&variant)) {
+            let size = (input.len() as u64 * bits / 8) as usize;
+
+            while bytes.len() < size {
+                bytes.push(0);
+            }
 
-        //Convert ascii bytes 0-9a-fA-F into digits
-        let input: Vec<_> = value.as_bytes().iter()
-            .rev()
-            .map(|&byte| Self::ascii_to_num(byte)).collect();
+            bytes.reverse();
+        }
 
-        convert_base::Convert::new(base, 256).convert::<u8, u8>(& input)
+        bytes
     }
 }
 
@@ -100,7 +130,7 @@ impl ToIR for crate::common::FixedFloat {
     fn identify(value: &str) -> Option<Vec<Variant>> {
         //If conversion to f64 succeeds, then conversion to f32 and f16 will also succeed
         if f64::from_str(value).is_ok() {
-            Some(vec![Variant("32-bit"), Variant("16-bit"), Variant("64-bit"),])
+            Some(vec![Variant::new("32-bit"), Variant::new("16-bit"), Variant::new("64-bit"),])
         } else {
             None
         }
@@ -110,7 +140,7 @@ impl ToIR for crate::common::FixedFloat {
 
         let number = f64::from_str(value).unwrap();
 
-        let bytes = match variant.0 {
+        let bytes = match variant.0.as_ref() {
             "16-bit" => Vec::from(f16::from_f64(number).to_le_bytes()),
             "32-bit" => Vec::from((number as f32).to_le_bytes()),
             "64-bit" => Vec::from(number.to_le_bytes()),
@@ -127,13 +157,13 @@ impl ToIR for crate::common::DateTime {
         let mut variants = Vec::new();
 
         if chrono::DateTime::parse_from_rfc2822(value).is_ok() {
-            variants.push(Variant("32-bit rfc2822"));
-            variants.push(Variant("64-bit rfc2822"));
+            variants.push(Variant::new("32-bit rfc2822"));
+            variants.push(Variant::new("64-bit rfc2822"));
         }
 
         if chrono::DateTime::parse_from_rfc3339(value).is_ok() {
-            variants.push(Variant("32-bit rfc3339"));
-            variants.push(Variant("64-bit rfc3339"));
+            variants.push(Variant::new("32-bit rfc3339"));
+            variants.push(Variant::new("64-bit rfc3339"));
         }
 
         if variants.is_empty() {
@@ -172,7 +202,7 @@ impl ToIR for crate::common::FixedInt {
             if variants.len() == 1 {
                 Some(Base2_16::decode(value, base2_16_variants.get(0).unwrap().clone()).len())
             } else {
-                if variants.contains(&Variant("Base 10")) {
+                if variants.contains(&Variant::new("Base 10")) {
                     Some()
                 }
                 Some(Base2_16::decode(value, base2_16_variants.get(0).unwrap().clone()).len())
@@ -188,43 +218,43 @@ impl ToIR for crate::common::FixedInt {
         println!("Size: {:?} {}", base2_16_size, value);
 
         if /*i128::from_str(value).is_ok() ||*/ base2_16_size <= Some(16) {
-            variants.insert(0, Variant("i128"));
+            variants.insert(0, Variant::new("i128"));
         }
 
         if /*u128::from_str(value).is_ok() ||*/ base2_16_size <= Some(16) {
-            variants.insert(0, Variant("u128"));
+            variants.insert(0, Variant::new("u128"));
         }
 
         if /*i64::from_str(value).is_ok() ||*/ base2_16_size <= Some(8) {
-            variants.insert(0, Variant("i64"));
+            variants.insert(0, Variant::new("i64"));
         }
 
         if /*u64::from_str(value).is_ok() ||*/ base2_16_size <= Some(8) {
-            variants.insert(0, Variant("u64"));
+            variants.insert(0, Variant::new("u64"));
         }
 
         if /*i32::from_str(value).is_ok() ||*/ base2_16_size <= Some(4) {
-            variants.insert(0, Variant("i32"));
+            variants.insert(0, Variant::new("i32"));
         }
 
         if /*u32::from_str(value).is_ok() ||*/ base2_16_size <= Some(4) {
-            variants.insert(0, Variant("u32"));
+            variants.insert(0, Variant::new("u32"));
         }
 
         if /*i16::from_str(value).is_ok() ||*/ base2_16_size <= Some(2) {
-            variants.insert(0, Variant("i16"));
+            variants.insert(0, Variant::new("i16"));
         }
 
         if /*u16::from_str(value).is_ok() ||*/ base2_16_size <= Some(2) {
-            variants.insert(0, Variant("u16"));
+            variants.insert(0, Variant::new("u16"));
         }
 
         if /*i8::from_str(value).is_ok() ||*/ base2_16_size <= Some(1) {
-            variants.insert(0, Variant("i8"));
+            variants.insert(0, Variant::new("i8"));
         }
 
         if /*u8::from_str(value).is_ok() ||*/ base2_16_size <= Some(1) {
-            variants.insert(0, Variant("u8"));
+            variants.insert(0, Variant::new("u8"));
         }
 
 
@@ -239,7 +269,7 @@ impl ToIR for crate::common::FixedInt {
 
         let base2_16_variant = FixedInt::get_base2_16_variant(value);
 
-        let (mut bytes, size_required) = match variant.0 {
+        let (mut bytes, size_required) = match variant.0.as_ref() {
             "i8" => (i8::from_str(value)
                          .map(|x| vec![x as u8])
                          .unwrap_or_else(|_| Base2_16::decode(value, base2_16_variant.unwrap())), 1),
@@ -286,8 +316,19 @@ impl ToIR for crate::common::FixedInt {
 }
 
 impl ToIR for crate::common::Unicode8 {
-    fn identify(_value: &str) -> Option<Vec<Variant>> {
-        Some(vec![Variant("")])
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        let mut variants = vec![Variant::new("")];
+
+        //CESU-8 only differs from UTF-8 for supplementary characters, and Modified UTF-8 also for NUL
+        if value.chars().any(|character| character as u32 > 0xFFFF) {
+            variants.push(Variant::new("CESU-8"));
+        }
+
+        if value.chars().any(|character| character as u32 > 0xFFFF || character == '\0') {
+            variants.push(Variant::new("Modified UTF-8"));
+        }
+
+        Some(variants)
     }
 
     fn decode(value: &str, variant: Variant) -> Vec<u8> {
@@ -295,20 +336,80 @@ impl ToIR for crate::common::Unicode8 {
             unsafe {
                 String::from(value).as_mut_vec().clone()
             }
+        } else if variant.0 == "CESU-8" {
+            UtfEncoding::encode(value, crate::utf::Variant::Cesu8)
+        } else if variant.0 == "Modified UTF-8" {
+            UtfEncoding::encode(value, crate::utf::Variant::ModifiedUtf8)
         } else {
             panic!("Invalid variant in ToIR Unicode8");
         }
     }
 }
 
+impl ToIR for crate::common::Unicode16 {
+    fn identify(_value: &str) -> Option<Vec<Variant>> {
+        Some(vec![Variant::new("without BOM"), Variant::new("with BOM")])
+    }
+
+    ///Encoded as little endian, and swapped if the user chooses big endian
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "without BOM" => UtfEncoding::encode(value, crate::utf::Variant::Utf16Le),
+            "with BOM" => [UtfEncoding::bom(crate::utf::Variant::Utf16Le), UtfEncoding::encode(value, crate::utf::Variant::Utf16Le)].concat(),
+            _ => panic!("Invalid variant in ToIR Unicode16"),
+        }
+    }
+}
+
+impl ToIR for crate::common::Unicode32 {
+    fn identify(_value: &str) -> Option<Vec<Variant>> {
+        Some(vec![Variant::new("without BOM"), Variant::new("with BOM")])
+    }
+
+    ///Encoded as little endian, and swapped if the user chooses big endian
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "without BOM" => UtfEncoding::encode(value, crate::utf::Variant::Utf32Le),
+            "with BOM" => [UtfEncoding::bom(crate::utf::Variant::Utf32Le), UtfEncoding::encode(value, crate::utf::Variant::Utf32Le)].concat(),
+            _ => panic!("Invalid variant in ToIR Unicode32"),
+        }
+    }
+}
+
+impl ToIR for crate::common::Codepage {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        //Codepages that keep ASCII give the same bytes as UTF-8 for ASCII input, so only EBCDIC is shown for it
+        let results = CODEPAGES.iter()
+            .filter(|codepage| !value.is_ascii() || !codepage.is_ascii_compatible())
+            .filter_map(|codepage| codepage.encode(value).ok().map(|bytes| (codepage.name, bytes)));
+
+        let variants: Vec<_> = CharacterSet::group(results).into_iter()
+            .map(|(names, _)| Variant::from_string(CharacterSet::describe(&names)))
+            .collect();
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match CharacterSet::find(&variant.0) {
+            Some(codepage) => codepage.encode(value).unwrap(),
+            None => panic!("Invalid variant in ToIR Codepage"),
+        }
+    }
+}
+
 impl ToIR for crate::common::IpV4 {
     fn identify(value: &str) -> Option<Vec<Variant>> {
         if value.parse::<std::net::SocketAddrV4>().is_ok() {
-            return Some(vec![Variant("with port")]);
+            return Some(vec![Variant::new("with port")]);
         }
 
         if value.parse::<std::net::Ipv4Addr>().is_ok() {
-            return Some(vec![Variant("without port")]);
+            return Some(vec![Variant::new("without port")]);
         }
 
         None
@@ -318,7 +419,7 @@ impl ToIR for crate::common::IpV4 {
 
         let mut result = Vec::new();
 
-        let (ipaddr, op_port) = match variant.0 {
+        let (ipaddr, op_port) = match variant.0.as_ref() {
             "with port" => {
                 let socket = value.parse::<std::net::SocketAddrV4>().unwrap();
 
@@ -347,11 +448,11 @@ impl ToIR for crate::common::IpV4 {
 impl ToIR for crate::common::IpV6 {
     fn identify(value: &str) -> Option<Vec<Variant>> {
         if value.parse::<std::net::SocketAddrV6>().is_ok() {
-            return Some(vec![Variant("with port")]);
+            return Some(vec![Variant::new("with port")]);
         }
 
         if value.parse::<std::net::Ipv6Addr>().is_ok() {
-            return Some(vec![Variant("without port")]);
+            return Some(vec![Variant::new("without port")]);
         }
 
         None
@@ -361,7 +462,7 @@ impl ToIR for crate::common::IpV6 {
 
         let mut result = Vec::new();
 
-        let (ipaddr, op_port) = match variant.0 {
+        let (ipaddr, op_port) = match variant.0.as_ref() {
             "with port" => {
                 let socket = value.parse::<std::net::SocketAddrV6>().unwrap();
 
@@ -389,7 +490,7 @@ impl ToIR for crate::common::IpV6 {
 
 impl ToIR for crate::common::Base91 {
     fn identify(_value: &str) -> Option<Vec<Variant>> {
-        Some(vec![Variant("")])
+        Some(vec![Variant::new("")])
     }
 
     fn decode(value: &str, _variant: Variant) -> Vec<u8> {
@@ -402,12 +503,24 @@ impl ToIR for crate::common::Base85 {
         let mut variants = Vec::new();
 
         if z85::decode(value.as_bytes()).is_ok() {
-            variants.push(Variant("z85"));
+            variants.push(Variant::new("z85"));
+        }
+
+        if Base85Encoding::decode(value, crate::base85::Variant::Ascii85).is_ok() {
+            variants.push(Variant::new("ascii85"));
+        }
+
+        if Base85Encoding::decode(value, crate::base85::Variant::Adobe).is_ok() {
+            variants.push(Variant::new("Adobe ascii85"));
         }
 
-        /*if ascii85::decode(value).is_ok() {
-            variants.push(Variant("ascii85"));
-        }*/
+        if Base85Encoding::decode(value, crate::base85::Variant::Btoa).is_ok() {
+            variants.push(Variant::new("btoa"));
+        }
+
+        if Base85Encoding::decode(value, crate::base85::Variant::Rfc1924).is_ok() {
+            variants.push(Variant::new("RFC 1924"));
+        }
 
         if variants.is_empty() {
             None
@@ -419,9 +532,12 @@ impl ToIR for crate::common::Base85 {
 
     fn decode(value: &str, variant: Variant) -> Vec<u8> {
 
-        match variant.0 {
+        match variant.0.as_ref() {
             "z85" => z85::decode(value.as_bytes()).unwrap(),
-            "ascii85" => panic!("Due to an issue with with the ascii85 crate, this is not suported"), //ascii85::decode(value).unwrap(),
+            "ascii85" => Base85Encoding::decode(value, crate::base85::Variant::Ascii85).unwrap(),
+            "Adobe ascii85" => Base85Encoding::decode(value, crate::base85::Variant::Adobe).unwrap(),
+            "btoa" => Base85Encoding::decode(value, crate::base85::Variant::Btoa).unwrap(),
+            "RFC 1924" => Base85Encoding::decode(value, crate::base85::Variant::Rfc1924).unwrap(),
             _ => panic!("Invalid variant in ToIR Base85")
         }
     }
@@ -429,38 +545,41 @@ impl ToIR for crate::common::Base85 {
 
 impl ToIR for crate::common::Base64 {
     fn identify(value: &str) -> Option<Vec<Variant>> {
+        //Base64 is often wrapped, such as in MIME bodies, so line breaks are ignored
+        let value = remove_line_breaks(value);
+
         let mut variants = Vec::new();
 
-        if base64::decode_config(value, base64::BCRYPT).is_ok() {
-            variants.push(Variant("Bcrypt"));
+        if base64::decode_config(&value, base64::BCRYPT).is_ok() {
+            variants.push(Variant::new("Bcrypt"));
         }
 
-        if base64::decode_config(value, base64::BINHEX).is_ok() {
-            variants.push(Variant("BinHex"));
+        if base64::decode_config(&value, base64::BINHEX).is_ok() {
+            variants.push(Variant::new("BinHex"));
         }
 
-        if base64::decode_config(value, base64::CRYPT).is_ok() {
-            variants.push(Variant("crypt"));
+        if base64::decode_config(&value, base64::CRYPT).is_ok() {
+            variants.push(Variant::new("crypt"));
         }
 
-        if base64::decode_config(value, base64::IMAP_MUTF7).is_ok() {
-            variants.push(Variant("IMAP UTF-7"));
+        if base64::decode_config(&value, base64::IMAP_MUTF7).is_ok() {
+            variants.push(Variant::new("IMAP UTF-7"));
         }
 
-        if base64::decode_config(value, base64::STANDARD).is_ok() {
-            variants.push(Variant("Standard"));
+        if base64::decode_config(&value, base64::STANDARD).is_ok() {
+            variants.push(Variant::new("Standard"));
         }
 
-        if base64::decode_config(value, base64::STANDARD_NO_PAD).is_ok() {
-            variants.push(Variant("Standard no padding"));
+        if base64::decode_config(&value, base64::STANDARD_NO_PAD).is_ok() {
+            variants.push(Variant::new("Standard no padding"));
         }
 
-        if base64::decode_config(value, base64::URL_SAFE).is_ok() {
-            variants.push(Variant("URL-safe"));
+        if base64::decode_config(&value, base64::URL_SAFE).is_ok() {
+            variants.push(Variant::new("URL-safe"));
         }
 
-        if base64::decode_config(value, base64::URL_SAFE_NO_PAD).is_ok() {
-            variants.push(Variant("URL-safe no padding"));
+        if base64::decode_config(&value, base64::URL_SAFE_NO_PAD).is_ok() {
+            variants.push(Variant::new("URL-safe no padding"));
         }
 
         if variants.is_empty() {
@@ -471,20 +590,203 @@ impl ToIR for crate::common::Base64 {
     }
 
     fn decode(value: &str, variant: Variant) -> Vec<u8> {
-        match variant.0 {
-            "Bcrypt" => base64::decode_config(value, base64::BCRYPT).unwrap(),
-            "BinHex" => base64::decode_config(value, base64::BINHEX).unwrap(),
-            "crypt" => base64::decode_config(value, base64::CRYPT).unwrap(),
-            "IMAP UTF-7" => base64::decode_config(value, base64::IMAP_MUTF7).unwrap(),
-            "Standard" => base64::decode_config(value, base64::STANDARD).unwrap(),
-            "Standard no padding" => base64::decode_config(value, base64::STANDARD_NO_PAD).unwrap(),
-            "URL-safe" => base64::decode_config(value, base64::STANDARD_NO_PAD).unwrap(),
-            "URL-safe no padding" => base64::decode_config(value, base64::URL_SAFE_NO_PAD).unwrap(),
+        let value = remove_line_breaks(value);
+
+        match variant.0.as_ref() {
+            "Bcrypt" => base64::decode_config(&value, base64::BCRYPT).unwrap(),
+            "BinHex" => base64::decode_config(&value, base64::BINHEX).unwrap(),
+            "crypt" => base64::decode_config(&value, base64::CRYPT).unwrap(),
+            "IMAP UTF-7" => base64::decode_config(&value, base64::IMAP_MUTF7).unwrap(),
+            "Standard" => base64::decode_config(&value, base64::STANDARD).unwrap(),
+            "Standard no padding" => base64::decode_config(&value, base64::STANDARD_NO_PAD).unwrap(),
+            "URL-safe" => base64::decode_config(&value, base64::URL_SAFE).unwrap(),
+            "URL-safe no padding" => base64::decode_config(&value, base64::URL_SAFE_NO_PAD).unwrap(),
             _ => panic!("Invalid variant in ToIr Base64"),
         }
     }
 }
 
+impl ToIR for crate::common::Base58 {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        //Every alphabet decodes an empty string to no bytes
+        if value.is_empty() {
+            return None;
+        }
+
+        let mut variants = Vec::new();
+
+        for variant in [Variant::new("Bitcoin"), Variant::new("Ripple"), Variant::new("Flickr")].iter() {
+            if bs58::decode(value).with_alphabet(Self::get_alphabet(variant)).into_vec().is_ok() {
+                variants.push(variant.clone());
+            }
+        }
+
+        //Base58Check is only used with the Bitcoin and Ripple alphabets
+        for variant in [Variant::new("Bitcoin Check"), Variant::new("Ripple Check")].iter() {
+            if bs58::decode(value).with_alphabet(Self::get_alphabet(variant)).with_check(None).into_vec().is_ok() {
+                variants.push(variant.clone());
+            }
+        }
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    ///Base58Check variants verify and remove the checksum, but keep the version byte as the first byte of the IR
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        let builder = bs58::decode(value).with_alphabet(Self::get_alphabet(&variant));
+
+        if Self::is_check(&variant) {
+            builder.with_check(None).into_vec().unwrap()
+        } else {
+            builder.into_vec().unwrap()
+        }
+    }
+}
+
+impl ToIR for crate::common::Base45 {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        if Base45Encoding::decode(value).is_ok_and(|bytes| Base45Encoding::is_plausible(&bytes)) {
+            Some(vec![Variant::new("")])
+        } else {
+            None
+        }
+    }
+
+    fn decode(value: &str, _variant: Variant) -> Vec<u8> {
+        Base45Encoding::decode(value).unwrap()
+    }
+}
+
+impl ToIR for crate::common::Base62 {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        let mut variants = Vec::new();
+
+        if Base62Encoding::decode(value, crate::compact::Variant::Standard).is_ok_and(|bytes| Base62Encoding::is_plausible(value, &bytes)) {
+            variants.push(Variant::new("Standard"));
+        }
+
+        if Base62Encoding::decode(value, crate::compact::Variant::Ksuid).is_ok() {
+            variants.push(Variant::new("KSUID"));
+        }
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "Standard" => Base62Encoding::decode(value, crate::compact::Variant::Standard).unwrap(),
+            "KSUID" => Base62Encoding::decode(value, crate::compact::Variant::Ksuid).unwrap(),
+            _ => panic!("Invalid variant in ToIR Base62"),
+        }
+    }
+}
+
+impl ToIR for crate::common::Attachment {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        let mut variants = Vec::new();
+
+        if AttachmentEncoding::decode(value, crate::attachment::Variant::Uuencode).is_ok() {
+            variants.push(Variant::new("uuencode"));
+        }
+
+        if AttachmentEncoding::decode(value, crate::attachment::Variant::Xxencode).is_ok() {
+            variants.push(Variant::new("xxencode"));
+        }
+
+        if AttachmentEncoding::decode(value, crate::attachment::Variant::Yenc).is_ok() {
+            variants.push(Variant::new("yEnc (ISO-8859-1)"));
+        }
+
+        if AttachmentEncoding::decode(value, crate::attachment::Variant::BinHex).is_ok() {
+            variants.push(Variant::new("BinHex 4.0"));
+        }
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "uuencode" => AttachmentEncoding::decode(value, crate::attachment::Variant::Uuencode).unwrap(),
+            "xxencode" => AttachmentEncoding::decode(value, crate::attachment::Variant::Xxencode).unwrap(),
+            "yEnc (ISO-8859-1)" => AttachmentEncoding::decode(value, crate::attachment::Variant::Yenc).unwrap(),
+            "BinHex 4.0" => AttachmentEncoding::decode(value, crate::attachment::Variant::BinHex).unwrap(),
+            _ => panic!("Invalid variant in ToIR Attachment"),
+        }
+    }
+}
+
+impl ToIR for crate::common::Mime {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        lazy_static! {
+            static ref ESCAPE: Regex = Regex::new("=[0-9A-Fa-f]{2}|=\\s*\\n").unwrap();
+        }
+
+        let mut variants = Vec::new();
+
+        //Only treat the string as quoted-printable if it has an escape or a soft line break
+        if ESCAPE.is_match(value) && MimeEncoding::decode(value, crate::mime::Variant::QuotedPrintable).is_ok() {
+            variants.push(Variant::new("Quoted-printable"));
+        }
+
+        //Each encoded-word names its own encoding, so a header mixing B and Q words is a single variant
+        if MimeEncoding::decode(value, crate::mime::Variant::EncodedWord).is_ok() {
+            variants.push(Variant::new("Encoded-word"));
+        }
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "Quoted-printable" => MimeEncoding::decode(value, crate::mime::Variant::QuotedPrintable).unwrap(),
+            "Encoded-word" => MimeEncoding::decode(value, crate::mime::Variant::EncodedWord).unwrap(),
+            _ => panic!("Invalid variant in ToIR Mime"),
+        }
+    }
+}
+
+impl ToIR for crate::common::Pem {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        //The variant reports the label and headers
+        PemEncoding::decode(value).ok().map(|document| vec![Variant::from_string(Self::describe(&document))])
+    }
+
+    fn decode(value: &str, _variant: Variant) -> Vec<u8> {
+        PemEncoding::decode(value).unwrap().data
+    }
+}
+
+impl ToIR for crate::common::DataUri {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        //The variant reports the media type and how the data is encoded
+        DataUriEncoding::decode(value).ok().map(|contents| {
+            let encoding = if contents.base64 { "base64" } else { "percent-encoded" };
+
+            vec![Variant::from_string(format!("{}, {}", contents.media_type, encoding))]
+        })
+    }
+
+    fn decode(value: &str, _variant: Variant) -> Vec<u8> {
+        DataUriEncoding::decode(value).unwrap().data
+    }
+}
+
 impl ToIR for crate::common::ByteList {
     fn identify(value: &str) -> Option<Vec<Variant>> {
         //Remove whitespace
@@ -497,7 +799,7 @@ impl ToIR for crate::common::ByteList {
         let cleaned = WHITESPACE.replace_all(value, "");
 
         if BYTE_LIST.is_match(&cleaned) {
-            Some(vec![Variant("")])
+            Some(vec![Variant::new("")])
         } else {
             None
         }
@@ -530,7 +832,7 @@ impl ToIR for crate::common::ByteList {
 impl ToIR for crate::common::UUID {
     fn identify(value: &str) -> Option<Vec<Variant>> {
         if uuid::Uuid::parse_str(value).is_ok() {
-            Some(vec![Variant("")])
+            Some(vec![Variant::new("")])
         } else {
             None
         }
@@ -553,11 +855,76 @@ impl ToIR for crate::common::EscapedString {
         let mut variants = Vec::new();
 
         if crate::escape::EscapeSequence::decode(value, crate::escape::Variant::C).is_ok() {
-            variants.push(Variant("C"))
+            variants.push(Variant::new("C"))
         }
 
         if crate::escape::EscapeSequence::decode(value, crate::escape::Variant::Python).is_ok() {
-            variants.push(Variant("Python"))
+            variants.push(Variant::new("Python"))
+        }
+
+        //Other dialects are only offered when the string uses their escapes, with dialects giving the same bytes grouped together
+        let dialects = crate::escape::Variant::ALL.iter().skip(2)
+            .filter(|dialect| dialect.is_used_in(value))
+            .filter_map(|dialect| crate::escape::EscapeSequence::decode(value, dialect.clone()).ok().map(|bytes| (dialect.name(), bytes)))
+            .collect();
+
+        variants.extend(Variant::grouped(dialects));
+
+        if variants.is_empty() {
+            None
+        } else {
+            Some(variants)
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        //Grouped dialects all give the same bytes, so the first is used
+        match variant.0.split(", ").next().and_then(crate::escape::Variant::from_name) {
+            Some(dialect) => crate::escape::EscapeSequence::decode(value, dialect).unwrap(),
+            None => panic!("Invalid variant in ToIR EscapedString"),
+        }
+    }
+}
+
+impl ToIR for crate::common::StringLiteral {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        let languages: Vec<_> = Language::ALL.iter()
+            .filter_map(|language| SourceString::decode(value, language.clone()).ok().map(|bytes| (language.name(), bytes)))
+            .collect();
+
+        if languages.is_empty() {
+            None
+        } else {
+            Some(Variant::grouped(languages))
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.split(", ").next().and_then(Language::from_name) {
+            Some(language) => SourceString::decode(value, language).unwrap(),
+            None => panic!("Invalid variant in ToIR StringLiteral"),
+        }
+    }
+}
+
+impl ToIR for crate::common::HtmlEntities {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        lazy_static! {
+            static ref REFERENCE: Regex = Regex::new("&(#[0-9]+|#[xX][0-9A-Fa-f]+|[A-Za-z][A-Za-z0-9]*);").unwrap();
+        }
+
+        if !REFERENCE.is_match(value) {
+            return None;
+        }
+
+        let mut variants = Vec::new();
+
+        if EntityEncoding::decode(value, crate::entity::Variant::Html).is_ok() {
+            variants.push(Variant::new("HTML"));
+        }
+
+        if EntityEncoding::decode(value, crate::entity::Variant::Xml).is_ok() {
+            variants.push(Variant::new("XML"));
         }
 
         if variants.is_empty() {
@@ -568,24 +935,196 @@ impl ToIR for crate::common::EscapedString {
     }
 
     fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "HTML" => EntityEncoding::decode(value, crate::entity::Variant::Html).unwrap().into_bytes(),
+            "XML" => EntityEncoding::decode(value, crate::entity::Variant::Xml).unwrap().into_bytes(),
+            _ => panic!("Invalid variant in ToIR HtmlEntities"),
+        }
+    }
+}
+
+impl ToIR for crate::common::RegexLiteral {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        //Only regexes with escapes are offered, as any plain text is also a regex
+        if !value.contains('\\') {
+            return None;
+        }
+
+        let flavours: Vec<_> = Flavour::ALL.iter()
+            .filter_map(|flavour| Quoting::unescape_regex(value, flavour.clone()).ok().map(|text| (flavour.name(), text.into_bytes())))
+            .collect();
 
-        match variant.0 {
-            "C" => crate::escape::EscapeSequence::decode(value, crate::escape::Variant::C).unwrap(),
-            "Python" => crate::escape::EscapeSequence::decode(value, crate::escape::Variant::Python).unwrap(),
-            _ => panic!("Invalid variant in ToIR EscapedString"),
+        if flavours.is_empty() {
+            None
+        } else {
+            Some(Variant::grouped(flavours))
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.split(", ").next().and_then(Flavour::from_name) {
+            Some(flavour) => Quoting::unescape_regex(value, flavour).unwrap().into_bytes(),
+            None => panic!("Invalid variant in ToIR RegexLiteral"),
         }
     }
 }
 
-impl ToIR for crate::common::UnicodeNames {
+impl ToIR for crate::common::SqlString {
     fn identify(value: &str) -> Option<Vec<Variant>> {
-        if unicode_names2::character(value).is_some() {
-            Some(vec![Variant("")])
+        let dialects: Vec<_> = Dialect::ALL.iter()
+            .filter_map(|dialect| Quoting::unquote_sql(value, dialect.clone()).ok().map(|bytes| (dialect.name(), bytes)))
+            .collect();
+
+        if dialects.is_empty() {
+            None
+        } else {
+            Some(Variant::grouped(dialects))
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.split(", ").next().and_then(Dialect::from_name) {
+            Some(dialect) => Quoting::unquote_sql(value, dialect).unwrap(),
+            None => panic!("Invalid variant in ToIR SqlString"),
+        }
+    }
+}
+
+impl ToIR for crate::common::ShellWord {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        //Words without quotes or backslashes are just text
+        if value.contains(['\'', '"', '\\']) && Quoting::unquote_shell(value).is_ok() {
+            Some(vec![Variant::new("")])
         } else {
             None
         }
     }
 
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        if variant.0.is_empty() {
+            Quoting::unquote_shell(value).unwrap().into_bytes()
+        } else {
+            panic!("Invalid variant in ToIR ShellWord")
+        }
+    }
+}
+
+impl ToIR for crate::common::UrlDecode {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        if !PercentEncoding::is_encoded(value) {
+            return None;
+        }
+
+        //Form encoding only differs when there is a '+' for a space
+        if value.contains('+') {
+            Some(vec![Variant::new("RFC 3986"), Variant::new("Form")])
+        } else {
+            Some(vec![Variant::new("RFC 3986")])
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "RFC 3986" => PercentEncoding::decode(value, false),
+            "Form" => PercentEncoding::decode(value, true),
+            _ => panic!("Invalid variant in ToIR UrlDecode"),
+        }
+    }
+}
+
+impl ToIR for crate::common::UrlComponents {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        let url = UrlParser::parse(value)?;
+
+        if UrlParser::normalize(&url) == value {
+            Some(vec![Variant::new("As written")])
+        } else {
+            Some(vec![Variant::new("As written"), Variant::new("Normalized")])
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "As written" => value.as_bytes().to_vec(),
+            "Normalized" => UrlParser::normalize(&UrlParser::parse(value).unwrap()).into_bytes(),
+            _ => panic!("Invalid variant in ToIR UrlComponents"),
+        }
+    }
+}
+
+impl ToIR for crate::common::Punycode {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        DomainName::decode_punycode(value).map(|_| vec![Variant::new("")])
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.as_ref() {
+            "" => DomainName::decode_punycode(value).unwrap().into_bytes(),
+            _ => panic!("Invalid variant in ToIR Punycode"),
+        }
+    }
+}
+
+impl ToIR for crate::common::Idna {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        if !DomainName::has_ace_label(value) {
+            return None;
+        }
+
+        let decoded: Vec<_> = Processing::ALL.iter()
+            .filter_map(|processing| DomainName::to_unicode(value, processing.clone()).ok().map(|unicode| (processing.name(), unicode.into_bytes())))
+            .collect();
+
+        if decoded.is_empty() {
+            None
+        } else {
+            Some(Variant::grouped(decoded))
+        }
+    }
+
+    fn decode(value: &str, variant: Variant) -> Vec<u8> {
+        match variant.0.split(", ").next().and_then(Processing::from_name) {
+            Some(processing) => DomainName::to_unicode(value, processing).unwrap().into_bytes(),
+            None => panic!("Invalid variant in ToIR Idna"),
+        }
+    }
+}
+
+impl ToIR for crate::common::UnicodeNames {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        //Unicode names only contain letters, digits, spaces and hyphens (unicode_names2 can panic on other strings)
+        let is_name = value.bytes().all(|x| x.is_ascii_alphanumeric() || x == b' ' || x == b'-');
+
+        if is_name && unicode_names2::character(value).is_some() {
+            return Some(vec![Variant::new("")]);
+        }
+
+        //Ranges and blocks give the whole range, followed by the first few characters in it
+        let range = crate::unicode::parse_range(value)
+            .map(|range| (range, String::from("")))
+            .or_else(|| crate::unicode::find_block(value).map(|block| ((block.start(), block.end()), format!(" ({})", block.name()))));
+
+        let characters = match &range {
+            Some(((start, end), _)) => crate::unicode::characters_in(*start, *end),
+            None if is_name && crate::unicode::is_name_query(value) => crate::unicode::search_names(value),
+            None => Vec::new(),
+        };
+
+        if characters.is_empty() {
+            return None;
+        }
+
+        let mut variants = Vec::new();
+
+        if let Some(((start, end), block)) = range {
+            variants.push(Variant::from_string(format!("U+{:04X}-U+{:04X} all {} characters{}", start, end, characters.len(), block)));
+        }
+
+        variants.extend(characters.into_iter().take(crate::unicode::MAX_RESULTS).map(|character| Variant::from_string(crate::unicode::label(character))));
+
+        Some(variants)
+    }
+
     fn decode(value: &str, variant: Variant) -> Vec<u8> {
         if variant.0 == "" {
             let mut bytes = Vec::new();
@@ -595,8 +1134,29 @@ impl ToIR for crate::common::UnicodeNames {
             bytes.extend_from_slice(character.to_string().as_bytes());
 
             bytes
+        } else if let Some((start, end)) = crate::unicode::parse_range(variant.0.split(' ').next().unwrap()) {
+            crate::unicode::characters_in(start, end).into_iter().collect::<String>().into_bytes()
+        } else if let Some(code_point) = variant.0.strip_prefix("U+") {
+            let code_point = u32::from_str_radix(code_point.split(' ').next().unwrap(), 16).unwrap();
+
+            char::from_u32(code_point).unwrap().to_string().into_bytes()
         } else {
-            panic!("Invalid variant in ToIR UUID");
+            panic!("Invalid variant in ToIR UnicodeNames");
+        }
+    }
+}
+
+impl ToIR for crate::common::CodePoints {
+    fn identify(value: &str) -> Option<Vec<Variant>> {
+        let (_, notations) = crate::unicode::parse_code_points(value)?;
+
+        Some(vec![Variant::from_string(notations.join(", "))])
+    }
+
+    fn decode(value: &str, _variant: Variant) -> Vec<u8> {
+        match crate::unicode::parse_code_points(value) {
+            Some((text, _)) => text.into_bytes(),
+            None => panic!("Invalid code points in ToIR CodePoints"),
         }
     }
 }
@@ -605,8 +1165,8 @@ impl ToIR for crate::common::Colour {
     fn identify(value: &str) -> Option<Vec<Variant>> {
         if value.len() == 7 {
             if &value[0..1] == "#" {
-                if crate::common::Base2_16::identify(&value[1..]).is_some() {
-                    return Some(vec![Variant("")]);
+                if value.as_bytes()[1..].iter().all(|&x| crate::common::Base2_16::is_hex(x)) {
+                    return Some(vec![Variant::new("")]);
                 }
             }
         }
@@ -616,7 +1176,10 @@ impl ToIR for crate::common::Colour {
 
     fn decode(value: &str, variant: Variant) -> Vec<u8> {
         if variant.0 == "" {
-            crate::common::Base2_16::decode(&value[1..], Variant("Base 16"))
+            //Padded hex keeps all 3 bytes in the order they're written, but the colour is read as a little endian number
+            let mut bytes = crate::common::Base2_16::decode(&value[1..], Variant::new("Base 16 padded"));
+            bytes.reverse();
+            bytes
         } else {
             panic!("Invalid variant in ToIR Colour");
         }
@@ -636,3 +1199,176 @@ impl ToIR for crate::common:: {
 }
 
  */
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Base58;
+    use crate::fromir::FromIR;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn base2_16_known_vectors() {
+        //The IR is little endian
+        assert_eq!(Base2_16::decode("zz", Variant::new("Base 36")), vec![0x0F, 0x05]);
+        assert_eq!(Base2_16::decode("ZZ", Variant::new("Base 36")), vec![0x0F, 0x05]);
+        assert_eq!(Base2_16::encode(&1295u16.to_le_bytes(), Variant::new("Base 36")).to_string(), "ZZ");
+
+        assert_eq!(Base2_16::decode("2101", Variant::new("Base 3")), vec![64]);
+        assert_eq!(Base2_16::encode(&[64], Variant::new("Base 3")).to_string(), "2101");
+
+        //"10" is the base itself, and base - 1 is the largest single digit
+        for base in 3..=36u64 {
+            let variant = Variant::new(Base2_16::base_name(base));
+
+            assert_eq!(Base2_16::decode("10", variant.clone()), vec![base as u8], "{}", base);
+            assert_eq!(Base2_16::encode(&[base as u8], variant.clone()).to_string(), "10", "{}", base);
+            assert_eq!(Base2_16::encode(&[base as u8 - 1], variant).to_string(), (Base2_16::num_to_ascii(base as u8 - 1, true) as char).to_string(), "{}", base);
+        }
+    }
+
+    #[test]
+    fn base2_16_groups_bases_with_the_same_value() {
+        //A single digit is the same number in every base it's valid in
+        let variants = Base2_16::identify("7").unwrap();
+        assert_eq!(variants.len(), 1);
+        assert!(variants[0].0.starts_with("Base 8, "));
+        assert_eq!(Base2_16::decode("7", variants[0].clone()), vec![7]);
+
+        //Only the smallest base and the common bases are listed
+        let names: Vec<_> = Base2_16::identify("a5").unwrap().into_iter().map(|variant| variant.0).collect();
+        assert_eq!(names, ["Base 11", "Base 16, Base 16 padded", "Base 36"]);
+
+        assert_eq!(Base2_16::identify("10").unwrap().len(), 5);
+        assert_eq!(FixedInt::get_base2_16_variant("10"), Some(Variant::new("Base 10")));
+    }
+
+    #[test]
+    fn base2_16_custom_alphabet() {
+        let alphabet = Base2_16::parse_alphabet("0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz").unwrap();
+        let variant = Base2_16::identify_custom("Zz", &alphabet).unwrap();
+
+        assert!(Base2_16::is_custom(&variant));
+        assert_eq!(Base2_16::get_base(&variant), 62);
+        assert_eq!(Base2_16::decode("Zz", variant.clone()), 2231u16.to_le_bytes());
+        assert_eq!(Base2_16::encode(&2231u16.to_le_bytes(), variant.clone()).to_string(), "Zz");
+
+        let id = "7n42DGM5Tflk9n8mt7Fhc7";
+        assert_eq!(Base2_16::encode(&Base2_16::decode(id, variant.clone()), variant).to_string(), id);
+
+        //Characters outside the alphabet aren't identified
+        assert_eq!(Base2_16::identify_custom("Zz!", &alphabet), None);
+
+        //Alphabets with a space or comma still decode, as the alphabet is read from the end of the variant name
+        let alphabet = Base2_16::parse_alphabet(" ,").unwrap();
+        let variant = Base2_16::custom_variant(&alphabet);
+        assert_eq!(Base2_16::decode(",  ", variant.clone()), vec![4]);
+        assert_eq!(Base2_16::encode(&[4], variant).to_string(), ",  ");
+    }
+
+    #[test]
+    fn base2_16_padded_keeps_leading_zero_bytes() {
+        assert_eq!(Base2_16::decode("0x0001", Variant::new("Base 16 padded")), vec![0, 1]);
+        assert_eq!(Base2_16::decode("0x0001", Variant::new("Base 16")), vec![1]);
+        assert_eq!(Base2_16::decode("0b0000000011111111", Variant::new("Base 2 padded")), vec![0, 255]);
+
+        assert_eq!(Base2_16::encode(&[0, 0, 1], Variant::new("Base 16 padded")).to_string(), "000001");
+        assert_eq!(Base2_16::encode(&[0xDE, 0xAD, 0xBE, 0xEF], Variant::new("Base 16 padded")).to_string(), "DEADBEEF");
+    }
+
+    #[test]
+    fn base2_16_padded_widths() {
+        //Base 2 is 8 digits per byte
+        assert_eq!(Base2_16::encode(&[1], Variant::new("Base 2 padded")).to_string(), "00000001");
+        assert_eq!(Base2_16::encode(&[0, 5], Variant::new("Base 2 padded")).to_string(), "0000000000000101");
+
+        //Base 8 is the natural width of the bits, rounded up to a whole digit
+        assert_eq!(Base2_16::encode(&[1], Variant::new("Base 8 padded")).to_string(), "001");
+        assert_eq!(Base2_16::encode(&[0, 1], Variant::new("Base 8 padded")).to_string(), "000001");
+        assert_eq!(Base2_16::encode(&[0, 0, 1], Variant::new("Base 8 padded")).to_string(), "00000001");
+        assert_eq!(Base2_16::decode("00000001", Variant::new("Base 8 padded")), vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn base2_16_padded_round_trip() {
+        let bytes = [0, 0, 0x12, 0, 0x34];
+
+        for name in ["Base 2 padded", "Base 8 padded", "Base 16 padded"] {
+            let encoded = Base2_16::encode(&bytes, Variant::new(name)).to_string();
+
+            assert_eq!(Base2_16::decode(&encoded, Variant::new(name)), bytes, "{}", name);
+        }
+
+        //Unpadded variants read the IR as a little endian number while padded variants write the bytes in order, and the IR survives going
+        //through either
+        for (unpadded, padded) in [("Base 2", "Base 2 padded"), ("Base 8", "Base 8 padded"), ("Base 16", "Base 16 padded")] {
+            let ir = Base2_16::decode("101", Variant::new(unpadded));
+            let padded_string = Base2_16::encode(&ir, Variant::new(padded)).to_string();
+
+            assert_eq!(Base2_16::encode(&Base2_16::decode(&padded_string, Variant::new(padded)), Variant::new(unpadded)).to_string(), "101", "{}", padded);
+        }
+
+        let ir = Base2_16::decode("00001234", Variant::new("Base 16 padded"));
+        assert_eq!(ir, vec![0, 0, 0x12, 0x34]);
+        assert_eq!(Base2_16::decode(&Base2_16::encode(&ir, Variant::new("Base 16")).to_string(), Variant::new("Base 16")), ir);
+    }
+
+    #[test]
+    fn base2_16_rejects_invalid_alphabets() {
+        assert_eq!(Base2_16::parse_alphabet("a"), Err(()));
+        assert_eq!(Base2_16::parse_alphabet("abca"), Err(()));
+        assert_eq!(Base2_16::parse_alphabet(&"x".repeat(65)), Err(()));
+        assert!(Base2_16::parse_alphabet(&('\u{100}'..'\u{140}').collect::<String>()).is_ok());
+    }
+
+    #[test]
+    fn base58_known_vectors() {
+        assert_eq!(Base58::decode("2NEpo7TZRRrLZSi2U", Variant::new("Bitcoin")), b"Hello World!");
+        assert_eq!(Base58::encode(b"Hello World!", Variant::new("Bitcoin")).to_string(), "2NEpo7TZRRrLZSi2U");
+
+        //Leading zero bytes are written as the first character of the alphabet
+        assert_eq!(Base58::encode(&[0, 0, 1], Variant::new("Bitcoin")).to_string(), "112");
+        assert_eq!(Base58::encode(&[0, 0, 1], Variant::new("Ripple")).to_string(), "rrp");
+    }
+
+    #[test]
+    fn base58_check_vectors() {
+        let address = hex("00010966776006953d5567439e5e39f86a0d273bee");
+
+        assert_eq!(Base58::decode("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM", Variant::new("Bitcoin Check")), address);
+        assert_eq!(Base58::encode(&address, Variant::new("Bitcoin Check")).to_string(), "16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvM");
+
+        //The account zero addresses, a version byte of 0 followed by 20 zero bytes
+        assert_eq!(Base58::decode("1111111111111111111114oLvT2", Variant::new("Bitcoin Check")), vec![0; 21]);
+        assert_eq!(Base58::decode("rrrrrrrrrrrrrrrrrrrrrhoLvTp", Variant::new("Ripple Check")), vec![0; 21]);
+
+        assert_eq!(Base58::encode(&address, Variant::new("Check version/payload")).to_string(), "version: 0x00, payload (20 byte(s)): 010966776006953d5567439e5e39f86a0d273bee");
+    }
+
+    #[test]
+    fn base58_check_rejects_bad_checksum() {
+        let variants = Base58::identify("16UwLL9Risc3QfPqBUvKofHmBQ7wMtjvN").unwrap();
+
+        assert!(variants.iter().any(|variant| variant.0 == "Bitcoin"));
+        assert!(!variants.iter().any(|variant| variant.0 == "Bitcoin Check"));
+    }
+
+    #[test]
+    fn base58_rejects_empty_input() {
+        assert_eq!(Base58::identify(""), None);
+    }
+
+    #[test]
+    fn base58_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+
+        for name in ["Bitcoin", "Ripple", "Flickr", "Bitcoin Check", "Ripple Check"] {
+            let encoded = Base58::encode(&bytes, Variant::new(name)).to_string();
+
+            assert_eq!(Base58::decode(&encoded, Variant::new(name)), bytes, "{}", name);
+        }
+    }
+}
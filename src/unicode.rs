@@ -0,0 +1,708 @@
+use ucd::{Codepoint, HangulSyllableType};
+use unicode_script::{UnicodeScript, Script};
+use unicode_security::{MixedScript, is_potential_mixed_script_confusable_char};
+use unicode_normalization::UnicodeNormalization;
+use unicode_blocks::UnicodeBlock;
+use unicode_segmentation::UnicodeSegmentation;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// unicode.rs implements the inspection of text one code point at a time, using the properties of the Unicode Character Database,
+/// the detection of characters used to hide or disguise text, normalization, searching for characters by name, range or block, and the
+/// breakdown of text into grapheme clusters and code point notations such as U+1F600
+
+#[derive(Debug, Clone, Copy)]
+pub enum Form {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+    CaseFold, //Full case folding, which may change the length of the text, such as 'ß' to 'ss'
+}
+
+#[derive(Debug, Clone)]
+///Properties of a single code point
+pub struct CodePointInfo {
+    pub character: char,
+    pub name: String,
+    pub category: String,
+    pub script: String,
+    pub block: String,
+    pub east_asian_width: String,
+    pub combining_class: u8,
+    pub is_combining: bool,
+}
+
+#[derive(Debug, Clone)]
+///A character that could hide or disguise part of the text, found at a byte offset
+pub struct Finding {
+    pub offset: usize,
+    pub character: char,
+    pub reason: String,
+}
+
+//Columns of the code point table
+const HEADINGS: [&str; 10] = ["Code point", "", "UTF-8", "UTF-16", "Name", "Category", "Script", "Block", "Width", "Combining"];
+
+//Most characters listed for a search
+pub const MAX_RESULTS: usize = 20;
+
+//Shortest query word that can match the start of a word in a name
+const MINIMUM_PREFIX: usize = 3;
+
+//Shown in front of combining marks so that they have something to combine with
+const DOTTED_CIRCLE: char = '\u{25CC}';
+
+lazy_static! {
+    //Built the first time they are needed, as both take a walk through every code point. Unified ideographs and Hangul syllables are
+    //left out of the names, which are made from their code point or jamo and are most of the named characters
+    static ref BLOCKS: Vec<UnicodeBlock> = blocks();
+    static ref NAMES: Vec<(char, String)> = (0..=char::MAX as u32)
+        .filter_map(char::from_u32)
+        .filter(|&character| !character.is_ideograph_unified() && !matches!(character.hangul_syllable_type(), Some(HangulSyllableType::LVSyllable | HangulSyllableType::LVTSyllable)))
+        .filter_map(|character| unicode_names2::name(character).map(|name| (character, name.to_string())))
+        .collect();
+}
+
+///Insert a space before each capital letter after the first, so that 'UppercaseLetter' becomes 'Uppercase letter'
+fn split_words(name: &str) -> String {
+    let mut words = String::new();
+
+    for (index, character) in name.chars().enumerate() {
+        if index > 0 && character.is_ascii_uppercase() {
+            words.push(' ');
+            words.push(character.to_ascii_lowercase());
+        } else {
+            words.push(character);
+        }
+    }
+
+    words
+}
+
+///Returns true if the string looks like text, which is when the only control characters are whitespace
+pub fn is_text(string: &str) -> bool {
+    string.chars().all(|character| !character.is_control() || character.is_ascii_whitespace())
+}
+
+pub fn normalize(text: &str, form: Form) -> String {
+    match form {
+        Form::Nfc => text.nfc().collect(),
+        Form::Nfd => text.nfd().collect(),
+        Form::Nfkc => text.nfkc().collect(),
+        Form::Nfkd => text.nfkd().collect(),
+        Form::CaseFold => caseless::default_case_fold_str(text),
+    }
+}
+
+///Name of the character, or a label such as '<control-000A>' for characters without a name
+pub fn name(character: char) -> String {
+    match unicode_names2::name(character) {
+        Some(name) => name.to_string(),
+        None => {
+            let label = match character.category() {
+                ucd::UnicodeCategory::Control => "control",
+                ucd::UnicodeCategory::PrivateUse => "private-use",
+                ucd::UnicodeCategory::Surrogate => "surrogate",
+                _ if character.is_noncharacter() => "noncharacter",
+                _ => "reserved",
+            };
+
+            format!("<{}-{:04X}>", label, character as u32)
+        }
+    }
+}
+
+pub fn inspect(character: char) -> CodePointInfo {
+    let category = character.category();
+
+    CodePointInfo {
+        character,
+        name: name(character),
+        category: split_words(&format!("{:?}", category)),
+        script: UnicodeScript::script(&character).full_name().replace('_', " "),
+        block: unicode_blocks::find_unicode_block(character).map(|block| block.name()).unwrap_or("No block").to_string(),
+        east_asian_width: split_words(&format!("{:?}", character.east_asian_width())),
+        combining_class: character.canonical_combining_class(),
+        is_combining: matches!(category, ucd::UnicodeCategory::NonspacingMark | ucd::UnicodeCategory::SpacingMark | ucd::UnicodeCategory::EnclosingMark),
+    }
+}
+
+impl CodePointInfo {
+    ///Character as it is shown in the table, with combining marks placed on a dotted circle and control characters left out
+    pub fn display(&self) -> String {
+        if self.is_combining {
+            format!("{}{}", DOTTED_CIRCLE, self.character)
+        } else if self.character.is_control() {
+            String::new()
+        } else {
+            self.character.to_string()
+        }
+    }
+
+    ///Number of columns the character takes up in a terminal
+    fn display_width(&self) -> usize {
+        if self.is_combining {
+            1
+        } else if matches!(self.character.category(), ucd::UnicodeCategory::Control | ucd::UnicodeCategory::Format) {
+            0
+        } else if matches!(self.character.east_asian_width(), ucd::EastAsianWidth::Wide | ucd::EastAsianWidth::FullWidth) {
+            2
+        } else {
+            1
+        }
+    }
+
+    fn row(&self) -> [String; 10] {
+        let mut utf8 = [0; 4];
+        let mut utf16 = [0; 2];
+
+        let utf8: Vec<_> = self.character.encode_utf8(&mut utf8).bytes().map(|byte| format!("{:02X}", byte)).collect();
+        let utf16: Vec<_> = self.character.encode_utf16(&mut utf16).iter().map(|unit| format!("{:04X}", unit)).collect();
+
+        let combining = if self.combining_class > 0 {
+            format!("yes (class {})", self.combining_class)
+        } else if self.is_combining {
+            String::from("yes")
+        } else {
+            String::from("no")
+        };
+
+        [
+            format!("U+{:04X}", self.character as u32),
+            self.display(),
+            utf8.join(" "),
+            utf16.join(" "),
+            self.name.clone(),
+            self.category.clone(),
+            self.script.clone(),
+            self.block.clone(),
+            self.east_asian_width.clone(),
+            combining,
+        ]
+    }
+}
+
+///Table with one row per code point of the text, with columns separated by 2 spaces
+pub fn table(text: &str) -> String {
+    let infos: Vec<_> = text.chars().map(inspect).collect();
+
+    let heading_row = HEADINGS.map(String::from);
+    let rows: Vec<_> = infos.iter().map(|info| info.row()).collect();
+
+    //Width of each column, where the character column is measured in terminal columns
+    let mut widths = heading_row.clone().map(|heading| heading.chars().count());
+
+    for (info, row) in infos.iter().zip(rows.iter()) {
+        for (column, cell) in row.iter().enumerate() {
+            let width = if column == 1 { info.display_width() } else { cell.chars().count() };
+
+            widths[column] = widths[column].max(width);
+        }
+    }
+
+    let format_row = |row: & [String; 10], character_width: usize| {
+        row.iter().enumerate().map(|(column, cell)| {
+            let width = if column == 1 { character_width } else { cell.chars().count() };
+
+            format!("{}{}", cell, " ".repeat(widths[column] - width))
+        }).collect::<Vec<_>>().join("  ").trim_end().to_string()
+    };
+
+    let mut lines = vec![format_row(&heading_row, 0)];
+
+    for (info, row) in infos.iter().zip(rows.iter()) {
+        lines.push(format_row(row, info.display_width()));
+    }
+
+    lines.join("\n")
+}
+
+///Reason a character is invisible or changes how the text around it is shown, if it does
+fn hidden_reason(character: char) -> Option<& 'static str> {
+    match character {
+        '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{180E}' | '\u{00AD}' => Some("zero-width character"),
+        '\u{202A}'..='\u{202E}' => Some("bidi embedding or override"),
+        '\u{2066}'..='\u{2069}' => Some("bidi isolate"),
+        '\u{200E}' | '\u{200F}' | '\u{061C}' => Some("bidi mark"),
+        '\u{00A0}' | '\u{2007}' | '\u{202F}' => Some("non-breaking space"),
+        '\u{1680}' | '\u{2000}'..='\u{200A}' | '\u{205F}' | '\u{3000}' => Some("unusual space"),
+        '\u{E0000}'..='\u{E007F}' => Some("tag character"),
+        _ => None,
+    }
+}
+
+///Find invisible characters, bidi controls, unusual spaces, tag characters and characters from another script that look like characters of the
+///script used by the rest of the word
+pub fn find_suspicious(text: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for (offset, character) in text.char_indices() {
+        if let Some(reason) = hidden_reason(character) {
+            findings.push(Finding {
+                offset,
+                character,
+                reason: String::from(reason),
+            });
+        }
+    }
+
+    let words = text.split(|x: char| !x.is_alphanumeric()).filter(|word| !word.is_empty());
+
+    for word in words {
+        if word.is_single_script() {
+            continue;
+        }
+
+        //The script used by most of the word is taken to be the intended one
+        let scripts: Vec<_> = word.chars()
+            .map(|character| UnicodeScript::script(&character))
+            .filter(|&script| script != Script::Common && script != Script::Inherited)
+            .collect();
+
+        let main_script = *scripts.iter().max_by_key(|&&script| scripts.iter().filter(|&&x| x == script).count()).unwrap();
+
+        let word_offset = word.as_ptr() as usize - text.as_ptr() as usize;
+
+        for (offset, character) in word.char_indices() {
+            let script = UnicodeScript::script(&character);
+
+            if script != main_script && script != Script::Common && script != Script::Inherited && is_potential_mixed_script_confusable_char(character) {
+                findings.push(Finding {
+                    offset: word_offset + offset,
+                    character,
+                    reason: format!("{} confusable in {} word '{}'", script.full_name(), main_script.full_name(), word),
+                });
+            }
+        }
+    }
+
+    findings.sort_by_key(|finding| finding.offset);
+
+    findings
+}
+
+///List of the suspicious characters in the text, followed by its confusable skeleton (UTS #39), which is the same for texts that look alike
+pub fn security_report(text: &str) -> String {
+    let findings = find_suspicious(text);
+
+    let mut lines: Vec<_> = findings.iter()
+        .map(|finding| format!("U+{:04X} at {}: {} ({})", finding.character as u32, finding.offset, name(finding.character), finding.reason))
+        .collect();
+
+    if lines.is_empty() {
+        lines.push(String::from("No invisible, bidi control, unusual space, tag or confusable characters"));
+    }
+
+    lines.push(format!("Skeleton: {}", unicode_security::skeleton(text).collect::<String>()));
+
+    lines.join("\n")
+}
+
+///Label for a search result, such as 'U+263A ☺ WHITE SMILING FACE'
+pub fn label(character: char) -> String {
+    format!("U+{:04X} {} {}", character as u32, inspect(character).display(), name(character))
+}
+
+///Every block, found by stepping from the end of each block to the next character with a block
+fn blocks() -> Vec<UnicodeBlock> {
+    let mut blocks = Vec::new();
+    let mut code_point = 0;
+
+    while code_point <= char::MAX as u32 {
+        match char::from_u32(code_point).and_then(unicode_blocks::find_unicode_block) {
+            Some(block) => {
+                code_point = block.end() + 1;
+                blocks.push(block);
+            },
+            None => code_point += 1,
+        }
+    }
+
+    blocks
+}
+
+///Block with the given name, ignoring case, spaces, hyphens and underscores like the loose matching of UAX #44
+pub fn find_block(query: &str) -> Option<UnicodeBlock> {
+    let loose = |name: &str| name.chars().filter(|x| !matches!(x, ' ' | '-' | '_')).collect::<String>().to_lowercase();
+
+    let query = loose(query);
+
+    BLOCKS.iter().find(|block| loose(block.name()) == query).copied()
+}
+
+///Parse a range of code points written as 'U+2190-U+21FF' or 'U+2190..U+21FF', where the second 'U+' is optional
+pub fn parse_range(query: &str) -> Option<(u32, u32)> {
+    let query = query.trim().strip_prefix("U+").or_else(|| query.trim().strip_prefix("u+"))?;
+
+    let (start, end) = query.split_once("..").or_else(|| query.split_once('-'))?;
+
+    let end = end.strip_prefix("U+").or_else(|| end.strip_prefix("u+")).unwrap_or(end);
+
+    let start = u32::from_str_radix(start, 16).ok()?;
+    let end = u32::from_str_radix(end, 16).ok()?;
+
+    if start <= end && end <= char::MAX as u32 {
+        Some((start, end))
+    } else {
+        None
+    }
+}
+
+///Assigned characters in the range, leaving out surrogates and unassigned code points
+pub fn characters_in(start: u32, end: u32) -> Vec<char> {
+    (start..=end)
+        .filter_map(char::from_u32)
+        .filter(|&character| character.category() != ucd::UnicodeCategory::Unassigned)
+        .collect()
+}
+
+///Check the name contains every query word, either as a whole word or the start of a word. Returns the number of words in the name that
+///were not matched and the number of query words matched as whole words, or None if a query word is missing
+fn match_name(name: &str, query: & [String]) -> Option<(usize, usize)> {
+    let words: Vec<_> = name.split([' ', '-']).collect();
+
+    let mut matched = Vec::new();
+    let mut whole = 0;
+
+    for query_word in query {
+        let position = match words.iter().position(|word| word == query_word) {
+            Some(position) => {
+                whole += 1;
+                position
+            },
+            None if query_word.len() >= MINIMUM_PREFIX => words.iter().position(|word| word.starts_with(query_word.as_str()))?,
+            None => return None,
+        };
+
+        if !matched.contains(&position) {
+            matched.push(position);
+        }
+    }
+
+    Some((words.len() - matched.len(), whole))
+}
+
+///Returns true if the query could be part of a character name, which is more than one word or a word in capitals like 'SNOWMAN'. Other
+///words are far more likely to be plain text than a search
+pub fn is_name_query(query: &str) -> bool {
+    query.trim().contains(' ') || query.bytes().any(|x| x.is_ascii_alphabetic()) && !query.bytes().any(|x| x.is_ascii_lowercase())
+}
+
+///Characters with names containing every word of the query, best matches first. The best matches have the fewest other words in their
+///name, then the most whole words matched, then the shortest name
+pub fn search_names(query: &str) -> Vec<char> {
+    let query: Vec<_> = query.split_whitespace().map(|word| word.to_uppercase()).collect();
+
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<_> = NAMES.iter()
+        .filter_map(|(character, name)| match_name(name, &query).map(|(extra, whole)| (extra, std::cmp::Reverse(whole), name.len(), *character)))
+        .collect();
+
+    matches.sort();
+
+    matches.into_iter().take(MAX_RESULTS).map(|(_, _, _, character)| character).collect()
+}
+
+///Returns true if the text has a grapheme cluster made of more than one code point, so it is worth breaking down
+pub fn has_sequences(text: &str) -> bool {
+    text.graphemes(true).any(|cluster| cluster.chars().nth(1).is_some())
+}
+
+///Describe the kinds of sequence that make up a grapheme cluster, such as 'emoji ZWJ sequence, emoji modifier sequence (skin type 3)'
+fn describe_cluster(cluster: &str) -> String {
+    let characters: Vec<_> = cluster.chars().collect();
+    let mut kinds = Vec::new();
+
+    let regional_indicators: String = characters.iter()
+        .filter(|&&x| ('\u{1F1E6}'..='\u{1F1FF}').contains(&x))
+        .map(|&x| (b'A' + (x as u32 - 0x1F1E6) as u8) as char)
+        .collect();
+
+    let tags: String = characters.iter()
+        .filter(|&&x| ('\u{E0020}'..='\u{E007E}').contains(&x))
+        .map(|&x| (x as u32 - 0xE0000) as u8 as char)
+        .collect();
+
+    if characters.len() == 1 {
+        kinds.push(String::from("single code point"));
+    }
+
+    if cluster == "\r\n" {
+        kinds.push(String::from("CRLF line break"));
+    }
+
+    if characters.contains(&'\u{200D}') {
+        kinds.push(String::from("emoji ZWJ sequence"));
+    }
+
+    if regional_indicators.len() == 2 {
+        kinds.push(format!("flag ({})", regional_indicators));
+    }
+
+    if !tags.is_empty() {
+        kinds.push(format!("tag sequence ({})", tags));
+    }
+
+    if characters.last() == Some(&'\u{20E3}') {
+        kinds.push(String::from("keycap sequence"));
+    }
+
+    //The 5 modifiers are for the Fitzpatrick skin types 1-2, 3, 4, 5 and 6
+    for &character in &characters {
+        match character {
+            '\u{1F3FB}' => kinds.push(String::from("emoji modifier sequence (skin type 1-2)")),
+            '\u{1F3FC}'..='\u{1F3FF}' => kinds.push(format!("emoji modifier sequence (skin type {})", character as u32 - 0x1F3FB + 2)),
+            _ => (),
+        }
+    }
+
+    if characters.contains(&'\u{FE0F}') {
+        kinds.push(String::from("emoji presentation"));
+    }
+
+    if characters.contains(&'\u{FE0E}') {
+        kinds.push(String::from("text presentation"));
+    }
+
+    if kinds.is_empty() {
+        let is_combining = |x: &char| matches!(x.category(), ucd::UnicodeCategory::NonspacingMark | ucd::UnicodeCategory::SpacingMark | ucd::UnicodeCategory::EnclosingMark);
+
+        if characters[1..].iter().all(is_combining) {
+            kinds.push(String::from("base with combining marks"));
+        } else if characters.iter().all(|&x| ('\u{1100}'..='\u{11FF}').contains(&x)) {
+            kinds.push(String::from("Hangul jamo syllable"));
+        } else {
+            kinds.push(String::from("grapheme cluster"));
+        }
+    }
+
+    kinds.join(", ")
+}
+
+///Split the text into extended grapheme clusters, listing the code points of each cluster and the kind of sequence it is
+pub fn grapheme_breakdown(text: &str) -> String {
+    let mut lines = Vec::new();
+
+    for (index, cluster) in text.graphemes(true).enumerate() {
+        lines.push(format!("{} '{}': {} ({} code point(s), {} byte(s))", index, cluster, describe_cluster(cluster), cluster.chars().count(), cluster.len()));
+
+        for character in cluster.chars() {
+            lines.push(format!("    U+{:04X} {}", character as u32, name(character)));
+        }
+    }
+
+    lines.join("\n")
+}
+
+///Name of each notation, in the order of the groups of the code point regex
+const NOTATIONS: [&str; 8] = ["U+XXXX", "HTML hex reference", "HTML decimal reference", "\\u{XXXX}", "\\uXXXX", "\\UXXXXXXXX", "%uXXXX", "Character literal"];
+
+///Parse a sequence of code points written in one or more notations, separated by nothing, whitespace or commas. UTF-16 surrogate pairs
+///written as two code units are combined. Returns the text and the notations used
+pub fn parse_code_points(string: &str) -> Option<(String, Vec<& 'static str>)> {
+    lazy_static! {
+        static ref CODE_POINT: Regex = Regex::new("U\\+([0-9A-Fa-f]{1,6})|&#[xX]([0-9A-Fa-f]{1,6});|&#([0-9]{1,7});|\\\\u\\{([0-9A-Fa-f]{1,6})\\}|\\\\u([0-9A-Fa-f]{4})|\\\\U([0-9A-Fa-f]{8})|%u([0-9A-Fa-f]{4})|'(.)'").unwrap();
+        static ref SEPARATOR: Regex = Regex::new("^[\\s,]*$").unwrap();
+    }
+
+    let mut code_points = Vec::new();
+    let mut notations = Vec::new();
+    let mut last_end = 0;
+
+    for captures in CODE_POINT.captures_iter(string) {
+        let whole = captures.get(0).unwrap();
+
+        if !SEPARATOR.is_match(&string[last_end..whole.start()]) {
+            return None;
+        }
+
+        last_end = whole.end();
+
+        let (index, digits) = captures.iter().enumerate().skip(1).find_map(|(index, group)| group.map(|group| (index - 1, group.as_str())))?;
+
+        let code_point = match index {
+            2 => digits.parse().ok()?,
+            7 => digits.chars().next().unwrap() as u32,
+            _ => u32::from_str_radix(digits, 16).ok()?,
+        };
+
+        code_points.push(code_point);
+
+        if !notations.contains(&NOTATIONS[index]) {
+            notations.push(NOTATIONS[index]);
+        }
+    }
+
+    if code_points.is_empty() || !SEPARATOR.is_match(&string[last_end..]) {
+        return None;
+    }
+
+    let mut text = String::new();
+    let mut code_points = code_points.into_iter().peekable();
+
+    while let Some(code_point) = code_points.next() {
+        let code_point = match (code_point, code_points.peek()) {
+            (0xD800..=0xDBFF, Some(&low @ 0xDC00..=0xDFFF)) => {
+                code_points.next();
+
+                0x10000 + ((code_point - 0xD800) << 10) + (low - 0xDC00)
+            },
+            _ => code_point,
+        };
+
+        //Lone surrogates and code points above U+10FFFF are not characters
+        text.push(char::from_u32(code_point)?);
+    }
+
+    Some((text, notations))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inspect_known_characters() {
+        let info = inspect('\u{263A}');
+
+        assert_eq!(info.name, "WHITE SMILING FACE");
+        assert_eq!(info.category, "Other symbol");
+        assert_eq!(info.block, "Miscellaneous Symbols");
+
+        let acute = inspect('\u{301}');
+
+        assert!(acute.is_combining);
+        assert_eq!(acute.combining_class, 230);
+        assert_eq!(acute.display(), "\u{25CC}\u{301}");
+
+        assert_eq!(name('\n'), "<control-000A>");
+        assert_eq!(name('\u{E000}'), "<private-use-E000>");
+        assert_eq!(name('\u{FFFF}'), "<noncharacter-FFFF>");
+    }
+
+    #[test]
+    fn table_rows() {
+        let table = table("a\u{1F600}");
+        let lines: Vec<_> = table.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Code point"));
+        assert!(lines[2].starts_with("U+1F600"));
+        assert!(lines[2].contains("F0 9F 98 80  D83D DE00  GRINNING FACE"));
+    }
+
+    #[test]
+    fn suspicious_characters() {
+        let findings = find_suspicious("pay\u{200B}pal \u{202E}txt.exe");
+
+        assert_eq!(findings.iter().map(|x| (x.offset, x.character, x.reason.as_str())).collect::<Vec<_>>(), vec![
+            (3, '\u{200B}', "zero-width character"),
+            (10, '\u{202E}', "bidi embedding or override"),
+        ]);
+
+        //Cyrillic 'а' in a Latin word
+        let findings = find_suspicious("p\u{430}ypal");
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].offset, 1);
+        assert_eq!(findings[0].reason, "Cyrillic confusable in Latin word 'p\u{430}ypal'");
+
+        assert!(find_suspicious("plain text, 123").is_empty());
+    }
+
+    #[test]
+    fn security_report_skeleton() {
+        assert_eq!(security_report("paypal"), "No invisible, bidi control, unusual space, tag or confusable characters\nSkeleton: paypal");
+        assert!(security_report("p\u{430}ypal").ends_with("Skeleton: paypal"));
+    }
+
+    #[test]
+    fn normalization_forms() {
+        let text = "e\u{301}\u{FB01}";
+
+        assert_eq!(normalize(text, Form::Nfc), "\u{E9}\u{FB01}");
+        assert_eq!(normalize("\u{E9}", Form::Nfd), "e\u{301}");
+        assert_eq!(normalize(text, Form::Nfkc), "\u{E9}fi");
+        assert_eq!(normalize(text, Form::Nfkd), "e\u{301}fi");
+        assert_eq!(normalize("Stra\u{DF}e", Form::CaseFold), "strasse");
+    }
+
+    #[test]
+    fn text_detection() {
+        assert!(is_text("line\r\n\ttab"));
+        assert!(!is_text("nul\0"));
+    }
+
+    #[test]
+    fn name_search() {
+        assert_eq!(search_names("smiling face").first(), Some(&'\u{263A}'));
+        assert_eq!(search_names("SNOWMAN").first(), Some(&'\u{2603}'));
+        assert!(search_names("smi face").contains(&'\u{263A}'));
+        assert!(search_names("smiling face").len() <= MAX_RESULTS);
+
+        //Query words shorter than 3 letters must match a whole word
+        assert!(search_names("sm face").is_empty());
+        assert!(search_names(" ").is_empty());
+    }
+
+    #[test]
+    fn name_queries() {
+        assert!(is_name_query("smiling face"));
+        assert!(is_name_query("SNOWMAN"));
+        assert!(!is_name_query("hello"));
+        assert!(!is_name_query("1234"));
+    }
+
+    #[test]
+    fn ranges_and_blocks() {
+        assert_eq!(parse_range("U+2190-U+21FF"), Some((0x2190, 0x21FF)));
+        assert_eq!(parse_range("u+41..5a"), Some((0x41, 0x5A)));
+        assert_eq!(parse_range("U+5A-41"), None);
+        assert_eq!(parse_range("U+10FFFF-110000"), None);
+
+        assert_eq!(characters_in(0x41, 0x43), vec!['A', 'B', 'C']);
+        assert!(characters_in(0xD800, 0xDFFF).is_empty());
+
+        let block = find_block("latin-1 supplement").unwrap();
+
+        assert_eq!((block.start(), block.end()), (0x80, 0xFF));
+        assert!(find_block("not a block").is_none());
+    }
+
+    #[test]
+    fn grapheme_clusters() {
+        assert!(!has_sequences("abc"));
+        assert!(has_sequences("e\u{301}"));
+
+        assert_eq!(describe_cluster("e\u{301}"), "base with combining marks");
+        assert_eq!(describe_cluster("\u{1F1EC}\u{1F1E7}"), "flag (GB)");
+        assert_eq!(describe_cluster("1\u{FE0F}\u{20E3}"), "keycap sequence, emoji presentation");
+        assert_eq!(describe_cluster("\u{1F44D}\u{1F3FD}"), "emoji modifier sequence (skin type 4)");
+        assert_eq!(describe_cluster("\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}"), "emoji ZWJ sequence");
+        assert_eq!(describe_cluster("\u{1F3F4}\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}"), "tag sequence (gbsct)");
+    }
+
+    #[test]
+    fn grapheme_breakdown_lines() {
+        assert_eq!(grapheme_breakdown("e\u{301}!"), "0 'e\u{301}': base with combining marks (2 code point(s), 3 byte(s))\n    U+0065 LATIN SMALL LETTER E\n    U+0301 COMBINING ACUTE ACCENT\n1 '!': single code point (1 code point(s), 1 byte(s))\n    U+0021 EXCLAMATION MARK");
+    }
+
+    #[test]
+    fn code_point_notations() {
+        assert_eq!(parse_code_points("U+263A"), Some((String::from("\u{263A}"), vec!["U+XXXX"])));
+        assert_eq!(parse_code_points("&#x41;&#66;"), Some((String::from("AB"), vec!["HTML hex reference", "HTML decimal reference"])));
+        assert_eq!(parse_code_points("\\u{1F600}, %u0041 'b'"), Some((String::from("\u{1F600}Ab"), vec!["\\u{XXXX}", "%uXXXX", "Character literal"])));
+
+        //Surrogate pairs written as two code units are combined
+        assert_eq!(parse_code_points("\\uD83D\\uDE00").map(|(text, _)| text), Some(String::from("\u{1F600}")));
+
+        //Lone surrogates, code points above U+10FFFF and other text between the code points
+        assert_eq!(parse_code_points("\\uD83D"), None);
+        assert_eq!(parse_code_points("U+110000"), None);
+        assert_eq!(parse_code_points("U+41 and U+42"), None);
+    }
+}
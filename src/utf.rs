@@ -0,0 +1,279 @@
+use std::convert::TryInto;
+
+/// utf.rs implements UTF-16 and UTF-32 in both byte orders, CESU-8 and Java's Modified UTF-8, and finds the invalid sequences in UTF-8
+
+#[derive(Debug, Clone)]
+pub enum Variant {
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+    Cesu8, //UTF-8 with supplementary characters written as a pair of 3 byte surrogates
+    ModifiedUtf8, //CESU-8 with NUL written as the 2 bytes C0 80, as used by Java's DataOutput and JNI
+}
+
+#[derive(Debug, Clone)]
+///An invalid sequence found in UTF-8, and why it is invalid
+pub struct InvalidSequence {
+    pub offset: usize,
+    pub length: usize,
+    pub reason: String,
+}
+
+const BOM: char = '\u{FEFF}';
+
+pub struct UtfEncoding;
+
+impl UtfEncoding {
+    pub fn encode(string: &str, variant: Variant) -> Vec<u8> {
+        match variant {
+            Variant::Utf16Le => string.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect(),
+            Variant::Utf16Be => string.encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect(),
+            Variant::Utf32Le => string.chars().flat_map(|character| (character as u32).to_le_bytes()).collect(),
+            Variant::Utf32Be => string.chars().flat_map(|character| (character as u32).to_be_bytes()).collect(),
+            Variant::Cesu8 => Self::encode_cesu8(string, false),
+            Variant::ModifiedUtf8 => Self::encode_cesu8(string, true),
+        }
+    }
+
+    pub fn decode(bytes: & [u8], variant: Variant) -> Result<String, ()> {
+        match variant {
+            Variant::Utf16Le => Self::decode_utf16(bytes, u16::from_le_bytes),
+            Variant::Utf16Be => Self::decode_utf16(bytes, u16::from_be_bytes),
+            Variant::Utf32Le => Self::decode_utf32(bytes, u32::from_le_bytes),
+            Variant::Utf32Be => Self::decode_utf32(bytes, u32::from_be_bytes),
+            Variant::Cesu8 => Self::decode_cesu8(bytes, false),
+            Variant::ModifiedUtf8 => Self::decode_cesu8(bytes, true),
+        }
+    }
+
+    ///Byte order mark in the given encoding
+    pub fn bom(variant: Variant) -> Vec<u8> {
+        Self::encode(&BOM.to_string(), variant)
+    }
+
+    ///Find every invalid sequence in the UTF-8. The bytes between the invalid sequences are valid UTF-8
+    pub fn diagnose_utf8(bytes: & [u8]) -> Vec<InvalidSequence> {
+        let mut invalid = Vec::new();
+        let mut index = 0;
+
+        while index < bytes.len() {
+            let (length, reason) = Self::check_utf8_sequence(&bytes[index..]);
+
+            if let Some(reason) = reason {
+                invalid.push(InvalidSequence {
+                    offset: index,
+                    length,
+                    reason,
+                });
+            }
+
+            index += length;
+        }
+
+        invalid
+    }
+
+    ///Check the sequence at the start of the bytes, returning its length and the reason it is invalid, if it is
+    fn check_utf8_sequence(bytes: & [u8]) -> (usize, Option<String>) {
+        //Length of the sequence, and the smallest code point which needs that length
+        let (length, minimum, lead) = match bytes[0] {
+            0x00..=0x7F => return (1, None),
+            0x80..=0xBF => return (1, Some(String::from("unexpected continuation byte"))),
+            byte @ 0xC0..=0xDF => (2, 0x80, (byte & 0x1F) as u32),
+            byte @ 0xE0..=0xEF => (3, 0x800, (byte & 0x0F) as u32),
+            byte @ 0xF0..=0xF7 => (4, 0x10000, (byte & 0x07) as u32),
+            _ => return (1, Some(String::from("invalid byte"))),
+        };
+
+        let continuations = bytes[1..].iter().take(length - 1).take_while(|&&x| x & 0xC0 == 0x80).count();
+
+        if continuations < length - 1 {
+            let reason = if continuations + 1 == bytes.len() {
+                format!("truncated, expected {} bytes but the input ends", length)
+            } else {
+                format!("truncated, expected {} bytes but found {}", length, continuations + 1)
+            };
+
+            return (continuations + 1, Some(reason));
+        }
+
+        let code_point = bytes[1..length].iter().fold(lead, |code_point, &x| code_point << 6 | (x & 0x3F) as u32);
+
+        let reason = if code_point < minimum {
+            Some(format!("overlong encoding of U+{:04X}", code_point))
+        } else if (0xD800..0xE000).contains(&code_point) {
+            Some(format!("surrogate U+{:04X}", code_point))
+        } else if code_point > 0x10FFFF {
+            Some(format!("out of range U+{:X}", code_point))
+        } else {
+            None
+        };
+
+        (length, reason)
+    }
+
+    fn decode_utf16(bytes: & [u8], to_unit: fn([u8; 2]) -> u16) -> Result<String, ()> {
+        if !bytes.len().is_multiple_of(2) {
+            return Err(());
+        }
+
+        let units = bytes.chunks(2).map(|unit| to_unit(unit.try_into().unwrap()));
+
+        char::decode_utf16(units).collect::<Result<String, _>>().map_err(|_| ())
+    }
+
+    fn decode_utf32(bytes: & [u8], to_unit: fn([u8; 4]) -> u32) -> Result<String, ()> {
+        if !bytes.len().is_multiple_of(4) {
+            return Err(());
+        }
+
+        bytes.chunks(4).map(|unit| char::from_u32(to_unit(unit.try_into().unwrap())).ok_or(())).collect()
+    }
+
+    fn encode_cesu8(string: &str, modified: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        //Each UTF-16 code unit, including surrogates, is written in its UTF-8 form
+        for unit in string.encode_utf16() {
+            match unit {
+                0 if modified => bytes.extend_from_slice(&[0xC0, 0x80]),
+                0x00..=0x7F => bytes.push(unit as u8),
+                0x80..=0x7FF => bytes.extend_from_slice(&[0xC0 | (unit >> 6) as u8, 0x80 | (unit & 0x3F) as u8]),
+                _ => bytes.extend_from_slice(&[0xE0 | (unit >> 12) as u8, 0x80 | ((unit >> 6) & 0x3F) as u8, 0x80 | (unit & 0x3F) as u8]),
+            }
+        }
+
+        bytes
+    }
+
+    ///Read the code unit starting at index, returning the unit and the number of bytes used
+    fn read_cesu8_unit(bytes: & [u8], index: usize, modified: bool) -> Result<(u32, usize), ()> {
+        let continuation = |offset: usize| bytes.get(index + offset)
+            .filter(|&&x| x & 0xC0 == 0x80)
+            .map(|&x| (x & 0x3F) as u32)
+            .ok_or(());
+
+        match bytes[index] {
+            0x00 if modified => Err(()),
+            byte @ 0x00..=0x7F => Ok((byte as u32, 1)),
+            byte @ 0xC0..=0xDF => {
+                let unit = ((byte & 0x1F) as u32) << 6 | continuation(1)?;
+
+                //Overlong forms are invalid, except for the 2 byte NUL of Modified UTF-8
+                if unit >= 0x80 || (modified && unit == 0) {
+                    Ok((unit, 2))
+                } else {
+                    Err(())
+                }
+            },
+            byte @ 0xE0..=0xEF => {
+                let unit = ((byte & 0x0F) as u32) << 12 | continuation(1)? << 6 | continuation(2)?;
+
+                if unit >= 0x800 {
+                    Ok((unit, 3))
+                } else {
+                    Err(())
+                }
+            },
+            _ => Err(()),
+        }
+    }
+
+    fn decode_cesu8(bytes: & [u8], modified: bool) -> Result<String, ()> {
+        let mut string = String::new();
+        let mut index = 0;
+
+        while index < bytes.len() {
+            let (unit, length) = Self::read_cesu8_unit(bytes, index, modified)?;
+            index += length;
+
+            let code_point = if (0xD800..0xDC00).contains(&unit) {
+                if index >= bytes.len() {
+                    return Err(());
+                }
+
+                let (low, length) = Self::read_cesu8_unit(bytes, index, modified)?;
+                index += length;
+
+                if !(0xDC00..0xE000).contains(&low) {
+                    return Err(());
+                }
+
+                0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00)
+            } else {
+                unit
+            };
+
+            //Unpaired low surrogates are not valid characters
+            string.push(char::from_u32(code_point).ok_or(())?);
+        }
+
+        Ok(string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf16_and_utf32_known_vectors() {
+        //U+1F600 is the surrogate pair D83D DE00 in UTF-16
+        assert_eq!(UtfEncoding::encode("A\u{1F600}", Variant::Utf16Le), vec![0x41, 0x00, 0x3D, 0xD8, 0x00, 0xDE]);
+        assert_eq!(UtfEncoding::encode("A\u{1F600}", Variant::Utf16Be), vec![0x00, 0x41, 0xD8, 0x3D, 0xDE, 0x00]);
+        assert_eq!(UtfEncoding::encode("A\u{1F600}", Variant::Utf32Be), vec![0, 0, 0, 0x41, 0, 0x01, 0xF6, 0x00]);
+
+        assert_eq!(UtfEncoding::bom(Variant::Utf16Le), vec![0xFF, 0xFE]);
+        assert_eq!(UtfEncoding::bom(Variant::Utf32Be), vec![0x00, 0x00, 0xFE, 0xFF]);
+    }
+
+    #[test]
+    fn utf16_and_utf32_reject_invalid_input() {
+        //An odd length, an unpaired surrogate, and a code point above U+10FFFF
+        assert!(UtfEncoding::decode(&[0x41, 0x00, 0x42], Variant::Utf16Le).is_err());
+        assert!(UtfEncoding::decode(&[0x3D, 0xD8, 0x41, 0x00], Variant::Utf16Le).is_err());
+        assert!(UtfEncoding::decode(&[0x00, 0x00, 0x11, 0x00], Variant::Utf32Le).is_err());
+    }
+
+    #[test]
+    fn cesu8_known_vectors() {
+        //Supplementary characters are a pair of 3 byte surrogates, and Modified UTF-8 writes NUL as C0 80
+        assert_eq!(UtfEncoding::encode("\u{1F600}", Variant::Cesu8), vec![0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+        assert_eq!(UtfEncoding::encode("\0\u{1F600}", Variant::ModifiedUtf8), vec![0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]);
+
+        assert_eq!(UtfEncoding::decode(&[0xC0, 0x80], Variant::ModifiedUtf8), Ok(String::from("\0")));
+        assert!(UtfEncoding::decode(&[0xC0, 0x80], Variant::Cesu8).is_err());
+        assert!(UtfEncoding::decode(&[0x00], Variant::ModifiedUtf8).is_err());
+
+        //4 byte UTF-8 and an unpaired high surrogate
+        assert!(UtfEncoding::decode(&[0xF0, 0x9F, 0x98, 0x80], Variant::Cesu8).is_err());
+        assert!(UtfEncoding::decode(&[0xED, 0xA0, 0xBD], Variant::Cesu8).is_err());
+    }
+
+    #[test]
+    fn utf8_diagnostics() {
+        let reasons = |bytes: & [u8]| UtfEncoding::diagnose_utf8(bytes).into_iter().map(|x| (x.offset, x.length, x.reason)).collect::<Vec<_>>();
+
+        assert!(reasons("valid \u{1F600}".as_bytes()).is_empty());
+
+        assert_eq!(reasons(&[0x41, 0x80, 0x42]), vec![(1, 1, String::from("unexpected continuation byte"))]);
+        assert_eq!(reasons(&[0xC0, 0xAF]), vec![(0, 2, String::from("overlong encoding of U+002F"))]);
+        assert_eq!(reasons(&[0xED, 0xA0, 0x80]), vec![(0, 3, String::from("surrogate U+D800"))]);
+        assert_eq!(reasons(&[0xF4, 0x90, 0x80, 0x80]), vec![(0, 4, String::from("out of range U+110000"))]);
+        assert_eq!(reasons(&[0xFF]), vec![(0, 1, String::from("invalid byte"))]);
+
+        //A truncated sequence only covers the bytes up to the next byte that isn't a continuation
+        assert_eq!(reasons(&[0xE2, 0x82, 0x41]), vec![(0, 2, String::from("truncated, expected 3 bytes but found 2"))]);
+        assert_eq!(reasons(&[0x41, 0xF0, 0x9F]), vec![(1, 2, String::from("truncated, expected 4 bytes but the input ends"))]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "\0ASCII, é, \u{20AC}, \u{FEFF}, \u{1F600} and \u{10FFFF}";
+
+        for variant in [Variant::Utf16Le, Variant::Utf16Be, Variant::Utf32Le, Variant::Utf32Be, Variant::Cesu8, Variant::ModifiedUtf8] {
+            assert_eq!(UtfEncoding::decode(&UtfEncoding::encode(text, variant.clone()), variant.clone()), Ok(String::from(text)), "{:?}", variant);
+        }
+    }
+}